@@ -8,24 +8,98 @@
 // - Cross-repo vortex state
 // - Governance for ecosystem coherence
 
-use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
-use near_sdk::collections::{LookupMap, UnorderedMap, Vector};
-use near_sdk::json_types::U128;
-use near_sdk::{env, near, AccountId, NearToken, PanicOnDefault};
+use std::collections::{HashMap, HashSet};
+
+use near_sdk::borsh::BorshSerialize;
+use near_sdk::collections::{LookupMap, UnorderedMap, UnorderedSet, Vector};
+use near_sdk::json_types::{Base64VecU8, U128};
+use near_sdk::{
+    env, ext_contract, near, AccountId, BorshStorageKey, Gas, NearToken, PanicOnDefault, Promise,
+    PromiseError,
+};
+
+// Contract schema version, stored in state at init and migrate
+const CONTRACT_VERSION: &str = "1.0.0";
+
+// NEP-297 event identity until governance configures its own
+const DEFAULT_EVENT_STANDARD: &str = "spiralsafe-vortex";
+const DEFAULT_EVENT_VERSION: &str = "1.0.0";
+
+// Cap on ATOMs recorded by a single batch_record_atoms call
+const MAX_BATCH_SIZE: usize = 100;
+
+// Byte-length caps on the string fields used as storage keys
+const MAX_ATOM_TAG_LEN: usize = 128;
+const MAX_REPO_LEN: usize = 64;
+const MAX_CONTRIBUTOR_LEN: usize = 64;
+const MAX_COMMIT_HASH_LEN: usize = 64;
+
+// Newest ATOMs scanned for read-time repo aggregation modes
+const MAX_AGGREGATION_WINDOW: u32 = 100;
+
+// Shortest abbreviated git hash accepted when commit hash validation is on
+const MIN_COMMIT_HASH_LEN: usize = 7;
+
+// Cap on tags resolved by a single get_atoms_batch view
+const MAX_VIEW_BATCH_SIZE: usize = 100;
+
+// Recent ecosystem averages retained for momentum estimates
+const VORTEX_HISTORY_LEN: usize = 32;
+
+// Recent per-repo averages retained for improvement tracking
+const COHERENCE_SERIES_LEN: usize = 32;
+
+// Weight of an ATOM carrying no weighted marker
+const DEFAULT_MARKER_WEIGHT: u8 = 1;
+
+// Contributor averages below this fall in the Low band
+const LOW_BAND_CEILING: u8 = 50;
+
+// Gas budgets for snap-in reward claims
+const GAS_FOR_FT_TRANSFER: Gas = Gas::from_tgas(10);
+const GAS_FOR_REWARD_CALLBACK: Gas = Gas::from_tgas(5);
+
+// Gas budgets for partner coherence syncs
+const GAS_FOR_PARTNER_VIEW: Gas = Gas::from_tgas(5);
+const GAS_FOR_PARTNER_CALLBACK: Gas = Gas::from_tgas(5);
+
+// NEP-141 fungible token used for snap-in rewards
+#[ext_contract(ext_ft)]
+pub trait FungibleToken {
+    fn ft_transfer(&mut self, receiver_id: AccountId, amount: U128, memo: Option<String>);
+}
+
+// Sibling provenance contract whose ecosystem average folds into ours
+#[ext_contract(ext_partner)]
+pub trait PartnerVortex {
+    fn get_vortex_state(&self) -> VortexState;
+}
 
 // ATOM decision record
 #[near(serializers = [json, borsh])]
 #[derive(Clone)]
 pub struct ATOMOnChain {
     pub atom_tag: String,
-    pub repo: String,
-    pub coherence_score: u8,  // 0-100
+    pub repo: String,  // Stored trimmed and lowercased; repo views accept any casing
+    pub coherence_score: u8,  // 0-max_coherence (100 unless governance rescales)
     pub phases_passed: Vec<String>,
     pub markers: Vec<String>,  // H&&S markers
     pub contributor: String,
     pub timestamp: String,
     pub commit_hash: String,
     pub pr_number: Option<u32>,
+    #[serde(default)]
+    pub expires_at_ns: Option<u64>,  // Block timestamp after which a certification lapses
+    #[serde(default)]
+    pub verified: bool,  // Set by governance via verify_atom
+    #[serde(default)]
+    pub sequence: u64,  // Assigned by the contract in record order
+    #[serde(default)]
+    pub block_height: u64,  // Block the ATOM was recorded in, set by the contract
+    #[serde(default)]
+    pub recorded_by: Option<AccountId>,  // Uploading account, set by the contract
+    #[serde(default)]
+    pub invalid: bool,  // Set by governance via invalidate_atom
 }
 
 // Vortex ecosystem state
@@ -33,8 +107,10 @@ pub struct ATOMOnChain {
 #[derive(Clone)]
 pub struct VortexState {
     pub total_atoms: u64,
+    pub total_coherence_sum: u64,
     pub average_coherence: u8,
-    pub snap_in_count: u64,
+    pub snap_in_count: u64,  // At-threshold new tags less invalidated ones; amends never adjust
+    pub contributor_count: u64,
     pub last_update: String,
 }
 
@@ -47,6 +123,182 @@ pub struct RepoState {
     pub total_coherence: u64,
     pub average_coherence: u8,
     pub last_snap_in: Option<String>,
+    pub weighted_coherence_total: u64,
+    pub weight_total: u64,
+    pub weighted_average_coherence: u8,  // Marker-weighted, alongside the raw average
+    pub display_name: Option<String>,
+    pub first_snap_in_atom: Option<String>,  // ATOM that first lifted the average to threshold
+    pub coherence_at_last_snapshot: u8,  // Average captured by snapshot_repo_coherence
+    pub contributor_count: u64,  // Distinct canonical contributors
+}
+
+// Dispute flag on a contested ATOM
+#[near(serializers = [json, borsh])]
+#[derive(Clone)]
+pub struct FlagRecord {
+    pub flagged_by: AccountId,
+    pub reason: String,
+    pub timestamp: String,
+    pub resolved: bool,
+}
+
+// Contributor aggregate tracking
+#[near(serializers = [json, borsh])]
+#[derive(Clone, PartialEq)]
+pub struct ContributorStats {
+    pub atom_count: u64,
+    pub coherence_sum: u64,
+    pub snap_in_count: u64,
+    pub first_seen: Option<String>,
+    pub last_seen: Option<String>,
+}
+
+// How get_repo_coherence_scored turns a repo's ATOMs into one headline figure
+#[near(serializers = [json, borsh])]
+#[derive(Clone)]
+pub enum AggregationMode {
+    Mean,  // Incrementally maintained average_coherence
+    MedianRecent(u32),  // Median of the newest n ATOMs, skipping invalidated ones
+    Min,  // Lowest of the newest MAX_AGGREGATION_WINDOW ATOMs, skipping invalidated ones
+}
+
+// Ecosystem health weighting (governance configurable)
+#[near(serializers = [json, borsh])]
+#[derive(Clone)]
+pub struct HealthWeights {
+    pub coherence: u8,
+    pub snap_in: u8,
+    pub contributors: u8,
+    pub contributor_target: u64,  // Contributor count that scores 100
+}
+
+// Lightweight ATOM projection for dashboard views
+#[near(serializers = [json])]
+pub struct AtomSummary {
+    pub atom_tag: String,
+    pub repo: String,
+    pub coherence_score: u8,
+    pub timestamp: String,
+}
+
+impl From<ATOMOnChain> for AtomSummary {
+    fn from(atom: ATOMOnChain) -> Self {
+        Self {
+            atom_tag: atom.atom_tag,
+            repo: atom.repo,
+            coherence_score: atom.coherence_score,
+            timestamp: atom.timestamp,
+        }
+    }
+}
+
+// Partial totals of an in-progress recompute_repo walk
+#[near(serializers = [borsh])]
+pub struct RepoRecompute {
+    pub next_index: u64,  // First repo index entry not yet walked
+    pub total_coherence: u64,
+    pub atom_count: u64,
+}
+
+// Contract storage consumption snapshot
+#[near(serializers = [json])]
+pub struct StorageStats {
+    pub storage_usage: u64,  // Bytes
+    pub storage_byte_cost: U128,  // yoctoNEAR per byte
+    pub storage_cost: U128,  // yoctoNEAR locked for storage
+    pub total_atoms: u64,
+}
+
+// Single ATOM bundled with its repo context and dispute status
+#[near(serializers = [json])]
+pub struct AtomDetail {
+    pub atom: ATOMOnChain,
+    pub met_threshold: bool,  // Against the repo's current effective threshold
+    pub repo_average_coherence: u8,
+    pub flag: Option<FlagRecord>,
+}
+
+// Baseline ATOM layout, replayed by migrate_legacy_atoms
+#[near(serializers = [borsh])]
+pub struct ATOMOnChainV0 {
+    pub atom_tag: String,
+    pub repo: String,
+    pub coherence_score: u8,
+    pub phases_passed: Vec<String>,
+    pub markers: Vec<String>,
+    pub contributor: String,
+    pub timestamp: String,
+    pub commit_hash: String,
+    pub pr_number: Option<u32>,
+}
+
+// Baseline vortex state layout
+#[near(serializers = [borsh])]
+pub struct VortexStateV0 {
+    pub total_atoms: u64,
+    pub average_coherence: u8,
+    pub snap_in_count: u64,
+    pub last_update: String,
+}
+
+// Baseline repo state layout
+#[near(serializers = [borsh])]
+pub struct RepoStateV0 {
+    pub repo: String,
+    pub atom_count: u64,
+    pub total_coherence: u64,
+    pub average_coherence: u8,
+    pub last_snap_in: Option<String>,
+}
+
+// Baseline contract state, read once by migrate
+#[near(serializers = [borsh])]
+pub struct OldState {
+    atoms: LookupMap<String, ATOMOnChainV0>,  // b"a"
+    repos: UnorderedMap<String, RepoStateV0>,  // b"r"
+    contributor_atoms: LookupMap<String, Vector<String>>,  // b"c", trails keyed by raw contributor
+    vortex_state: VortexStateV0,
+    owner: AccountId,
+    snap_in_threshold: u8,
+}
+
+// Storage prefixes; nested collections key off a hash of their owner so no
+// repo, marker, tag or contributor string can alias another collection
+#[derive(BorshSerialize, BorshStorageKey)]
+#[borsh(crate = "near_sdk::borsh")]
+enum StorageKey {
+    Atoms,
+    Repos,
+    ContributorAtoms,
+    ContributorTrail { contributor_hash: Vec<u8> },
+    ContributorLinks,
+    ContributorAliases,
+    ContributorStats,
+    RepoAtoms,
+    RepoAtomIndex { repo_hash: Vec<u8> },
+    AtomFeed,
+    RepoContributors,
+    RepoContributorSet { repo_hash: Vec<u8> },
+    RepoRecomputes,
+    MarkerAtoms,
+    MarkerAtomIndex { marker_hash: Vec<u8> },
+    IdempotencyKeys,
+    AttestationKeys,
+    CoherenceSeries,
+    MarkerWeights,
+    RepoThresholds,
+    RepoAggregationModes,
+    RepoMinRecordCoherence,
+    AtomHistory,
+    AtomVersions { atom_tag_hash: Vec<u8> },
+    Flags,
+    PhaseCounts,
+    RepoPhaseCounts,
+    RepoPhaseCountMap { repo_hash: Vec<u8> },
+    Admins,
+    PendingRewards,
+    ClaimableRewards,
+    ReleasedRewards,
 }
 
 // Main contract
@@ -62,12 +314,102 @@ pub struct SpiralSafeVortex {
     // Contributor trail: contributor -> Vec<atom_tag>
     contributor_atoms: LookupMap<String, Vector<String>>,
 
+    // Contributor aliasing: alias -> canonical, canonical -> aliases
+    contributor_links: LookupMap<String, String>,
+    contributor_aliases: LookupMap<String, Vec<String>>,
+
+    // Contributor stats: contributor -> ContributorStats
+    contributor_stats: UnorderedMap<String, ContributorStats>,
+
+    // Repo index: repo_name -> Vec<atom_tag> (record order)
+    repo_atoms: LookupMap<String, Vector<String>>,
+
+    // Global feed: every atom_tag in record order
+    atom_feed: Vector<String>,
+
+    // Repo contributors: repo_name -> canonical contributors seen
+    repo_contributors: LookupMap<String, UnorderedSet<String>>,
+
+    // In-progress recompute_repo walks: repo_name -> partial totals
+    repo_recomputes: LookupMap<String, RepoRecompute>,
+
+    // Marker index: marker -> Vec<atom_tag> (record order)
+    marker_atoms: LookupMap<String, Vector<String>>,
+
+    // Idempotent record keys: idempotency_key -> atom_tag
+    idempotency_keys: LookupMap<String, String>,
+
+    // Self-attestation keys: atom_tag -> ed25519 public key
+    attestation_keys: LookupMap<String, Vec<u8>>,
+
+    // Repo coherence series: repo_name -> average after each recent ATOM, oldest first
+    coherence_series: LookupMap<String, Vec<u8>>,
+
+    // Marker weights for the weighted repo average: marker -> weight
+    marker_weights: LookupMap<String, u8>,
+    weighted_averages_enabled: bool,
+
+    // Per-repo snap-in threshold overrides: repo_name -> threshold
+    repo_thresholds: LookupMap<String, u8>,
+
+    // Per-repo aggregation overrides: repo_name -> mode (absent means Mean)
+    repo_aggregation_modes: LookupMap<String, AggregationMode>,
+
+    // Per-repo recording floor overrides: repo_name -> minimum coherence
+    repo_min_record_coherence: LookupMap<String, u8>,
+
+    // Amendment trail: atom_tag -> prior versions, oldest first
+    atom_history: LookupMap<String, Vector<ATOMOnChain>>,
+
+    // Dispute flags: atom_tag -> FlagRecord
+    flags: LookupMap<String, FlagRecord>,
+
+    // Allowed phases in pipeline order; empty accepts any phase
+    phase_registry: Vec<String>,
+    phase_order_enforced: bool,  // Require phases_passed to be a prefix of the registry
+
+    // Phase tallies: phase -> atoms passed, globally and per repo
+    phase_counts: UnorderedMap<String, u64>,
+    repo_phase_counts: LookupMap<String, UnorderedMap<String, u64>>,
+
     // Global vortex state
     vortex_state: VortexState,
+    atom_sequence_counter: u64,  // Next sequence to assign
+    vortex_history: Vec<u8>,  // Ecosystem average after each recent ATOM, oldest first
 
     // Governance
     owner: AccountId,
+    admins: UnorderedSet<AccountId>,  // May tune thresholds and marker weights
     snap_in_threshold: u8,  // Default 70
+    min_record_coherence: u8,  // ATOMs below this are refused, default 0
+    validate_commit_hash: bool,  // Require git-style hex hashes, default true
+    max_coherence: u8,  // Top of the coherence_score scale, default 100
+    marker_diversity_max: u8,  // Distinct markers that score 100, default 10
+    health_weights: HealthWeights,
+
+    // Pilot capacity: None means unlimited
+    max_total_atoms: Option<u64>,
+
+    // Snap-in rewards: held per ATOM until verified, then claimable per account
+    reward_token: Option<AccountId>,
+    reward_per_snap_in: u128,
+    pending_rewards: LookupMap<String, u128>,
+    claimable_rewards: LookupMap<AccountId, u128>,
+    released_rewards: LookupMap<String, u128>,  // Per ATOM, so amends never re-hold a paid reward
+
+    // Partner bridge: last synced state of the sibling contract
+    partner_contract: Option<AccountId>,
+    partner_state: Option<VortexState>,
+
+    // Baseline repo entries not yet cleared by migrate_legacy_atoms
+    legacy_repos: Option<UnorderedMap<String, RepoStateV0>>,
+
+    // CONTRACT_VERSION this state was last written by
+    version: String,
+
+    // NEP-297 event identity, distinct per deployment
+    event_standard: String,
+    event_version: String,
 }
 
 #[near]
@@ -75,18 +417,161 @@ impl SpiralSafeVortex {
     #[init]
     pub fn new(owner: AccountId) -> Self {
         Self {
-            atoms: LookupMap::new(b"a"),
-            repos: UnorderedMap::new(b"r"),
-            contributor_atoms: LookupMap::new(b"c"),
+            atoms: LookupMap::new(StorageKey::Atoms),
+            repos: UnorderedMap::new(StorageKey::Repos),
+            contributor_atoms: LookupMap::new(StorageKey::ContributorAtoms),
+            contributor_links: LookupMap::new(StorageKey::ContributorLinks),
+            contributor_aliases: LookupMap::new(StorageKey::ContributorAliases),
+            contributor_stats: UnorderedMap::new(StorageKey::ContributorStats),
+            repo_atoms: LookupMap::new(StorageKey::RepoAtoms),
+            atom_feed: Vector::new(StorageKey::AtomFeed),
+            repo_contributors: LookupMap::new(StorageKey::RepoContributors),
+            repo_recomputes: LookupMap::new(StorageKey::RepoRecomputes),
+            marker_atoms: LookupMap::new(StorageKey::MarkerAtoms),
+            idempotency_keys: LookupMap::new(StorageKey::IdempotencyKeys),
+            attestation_keys: LookupMap::new(StorageKey::AttestationKeys),
+            coherence_series: LookupMap::new(StorageKey::CoherenceSeries),
+            marker_weights: LookupMap::new(StorageKey::MarkerWeights),
+            weighted_averages_enabled: false,
+            repo_thresholds: LookupMap::new(StorageKey::RepoThresholds),
+            repo_aggregation_modes: LookupMap::new(StorageKey::RepoAggregationModes),
+            repo_min_record_coherence: LookupMap::new(StorageKey::RepoMinRecordCoherence),
+            atom_history: LookupMap::new(StorageKey::AtomHistory),
+            flags: LookupMap::new(StorageKey::Flags),
+            phase_registry: Vec::new(),
+            phase_order_enforced: false,
+            phase_counts: UnorderedMap::new(StorageKey::PhaseCounts),
+            repo_phase_counts: LookupMap::new(StorageKey::RepoPhaseCounts),
             vortex_state: VortexState {
                 total_atoms: 0,
+                total_coherence_sum: 0,
                 average_coherence: 0,
                 snap_in_count: 0,
+                contributor_count: 0,
                 last_update: env::block_timestamp().to_string(),
             },
+            atom_sequence_counter: 0,
+            vortex_history: Vec::new(),
             owner,
+            admins: UnorderedSet::new(StorageKey::Admins),
             snap_in_threshold: 70,
+            min_record_coherence: 0,
+            validate_commit_hash: true,
+            max_coherence: 100,
+            marker_diversity_max: 10,
+            health_weights: HealthWeights {
+                coherence: 50,
+                snap_in: 30,
+                contributors: 20,
+                contributor_target: 10,
+            },
+            max_total_atoms: None,
+            reward_token: None,
+            reward_per_snap_in: 0,
+            pending_rewards: LookupMap::new(StorageKey::PendingRewards),
+            claimable_rewards: LookupMap::new(StorageKey::ClaimableRewards),
+            released_rewards: LookupMap::new(StorageKey::ReleasedRewards),
+            partner_contract: None,
+            partner_state: None,
+            legacy_repos: None,
+            version: CONTRACT_VERSION.to_string(),
+            event_standard: DEFAULT_EVENT_STANDARD.to_string(),
+            event_version: DEFAULT_EVENT_VERSION.to_string(),
+        }
+    }
+
+    /// Re-initialize from the baseline state after a code upgrade
+    ///
+    /// Carries the owner and snap-in threshold into the current layout and
+    /// stamps the deployed CONTRACT_VERSION so get_version reports a match.
+    /// Repo, contributor and vortex aggregates start empty; baseline ATOMs
+    /// are replayed into them, and into the current RepoState fields, with
+    /// migrate_legacy_atoms.
+    #[private]
+    #[init(ignore_state)]
+    pub fn migrate() -> Self {
+        let old: OldState = env::state_read().expect("No baseline contract state to migrate");
+        let mut state = Self::new(old.owner);
+        state.snap_in_threshold = old.snap_in_threshold;
+        state.legacy_repos = Some(old.repos);
+        env::log_str(&format!("MIGRATED: baseline -> {}", CONTRACT_VERSION));
+        state
+    }
+
+    /// Replay a batch of baseline ATOMs into the current layout (owner only)
+    ///
+    /// Baseline storage cannot be enumerated, so operators pass the tags
+    /// from their own records. Each ATOM is removed from the baseline maps,
+    /// along with its baseline repo entry, and stored without validation,
+    /// rewards or events. A baseline contributor trail goes once all of its
+    /// tags are replayed. Tags already replayed are skipped. Returns the
+    /// number of ATOMs imported.
+    pub fn migrate_legacy_atoms(&mut self, atom_tags: Vec<String>) -> u64 {
+        self.assert_owner();
+        assert!(
+            atom_tags.len() <= MAX_BATCH_SIZE,
+            "Batch of {} ATOMs exceeds max batch size {}",
+            atom_tags.len(),
+            MAX_BATCH_SIZE
+        );
+        assert_eq!(self.max_coherence, 100, "Baseline ATOMs are scored out of 100");
+
+        let mut legacy_atoms: LookupMap<String, ATOMOnChainV0> = LookupMap::new(b"a");
+        let mut legacy_trails: LookupMap<String, Vector<String>> = LookupMap::new(b"c");
+
+        let mut imported = 0;
+        for atom_tag in atom_tags {
+            let old = match legacy_atoms.remove(&atom_tag) {
+                Some(old) => old,
+                None => continue,
+            };
+            if let Some(legacy_repos) = self.legacy_repos.as_mut() {
+                legacy_repos.remove(&old.repo);
+            }
+            // Unwind replayed tags off the trail's tail; it goes once all of them are replayed
+            if let Some(mut trail) = legacy_trails.get(&old.contributor) {
+                while trail
+                    .get(trail.len().saturating_sub(1))
+                    .is_some_and(|tag| !legacy_atoms.contains_key(&tag))
+                {
+                    trail.pop();
+                }
+                if trail.is_empty() {
+                    legacy_trails.remove(&old.contributor);
+                } else {
+                    legacy_trails.insert(&old.contributor, &trail);
+                }
+            }
+            if self.atoms.get(&atom_tag).is_some() {
+                env::log_str(&format!("MIGRATION SKIPPED: {} already recorded", atom_tag));
+                continue;
+            }
+
+            let atom = ATOMOnChain {
+                atom_tag,
+                repo: normalize_repo(&old.repo),
+                coherence_score: old.coherence_score,
+                phases_passed: dedup_preserving_order(old.phases_passed),
+                markers: dedup_preserving_order(old.markers),
+                contributor: old.contributor,
+                timestamp: old.timestamp,
+                commit_hash: old.commit_hash,
+                pr_number: old.pr_number,
+                expires_at_ns: None,
+                verified: false,
+                sequence: self.atom_sequence_counter,
+                block_height: env::block_height(),
+                recorded_by: None,
+                invalid: false,
+            };
+            self.atom_sequence_counter += 1;
+            self.store_new_atom(&atom);
+            imported += 1;
+        }
+        if self.legacy_repos.as_ref().is_some_and(|repos| repos.is_empty()) {
+            self.legacy_repos = None;
         }
+        imported
     }
 
     // ==================== CHANGE METHODS ====================
@@ -94,39 +579,116 @@ impl SpiralSafeVortex {
     /// Record a single ATOM decision
     #[payable]
     pub fn record_atom(&mut self, atom: ATOMOnChain) -> String {
-        // Validate
-        assert!(atom.coherence_score <= 100, "Invalid coherence score");
-        assert!(!atom.atom_tag.is_empty(), "ATOM tag required");
+        self.internal_record_atom(atom, None)
+    }
 
-        // Store ATOM
-        let atom_tag = atom.atom_tag.clone();
-        self.atoms.insert(&atom_tag, &atom);
+    /// Record an ATOM at most once per client-supplied idempotency key
+    ///
+    /// A retry with a key already seen skips the write and returns the
+    /// original result, so uploaders can safely resubmit after a timeout.
+    #[payable]
+    pub fn record_atom_idempotent(
+        &mut self,
+        atom: ATOMOnChain,
+        idempotency_key: Option<String>,
+    ) -> String {
+        let key = match idempotency_key {
+            Some(key) => key,
+            None => return self.internal_record_atom(atom, None),
+        };
 
-        // Update repo state
-        self.update_repo_state(&atom);
+        if let Some(atom_tag) = self.idempotency_keys.get(&key) {
+            let original = self.atoms.get(&atom_tag).expect("ATOM not found for idempotency key");
+            return format!("{}:{}", original.block_height, atom_tag);
+        }
 
-        // Update contributor trail
-        self.add_to_contributor_trail(&atom);
+        self.idempotency_keys.insert(&key, &atom.atom_tag);
+        self.internal_record_atom(atom, None)
+    }
 
-        // Update global vortex state
-        self.update_vortex_state(&atom);
+    /// Record an ATOM, rejecting it unless the caller's expected sequence is next
+    ///
+    /// Lets off-chain clients detect concurrent writes between reading
+    /// get_atom_sequence and submitting.
+    #[payable]
+    pub fn record_atom_sequenced(
+        &mut self,
+        atom: ATOMOnChain,
+        expected_sequence: Option<u64>,
+    ) -> String {
+        self.internal_record_atom(atom, expected_sequence)
+    }
 
-        // Check for ecosystem snap-in
-        if atom.coherence_score >= self.snap_in_threshold {
-            self.vortex_state.snap_in_count += 1;
-            env::log_str(&format!(
-                "SNAP-IN: {} achieved {}% coherence",
-                atom.atom_tag, atom.coherence_score
-            ));
-        }
+    /// Record an ATOM whose coherence is self-attested by its contributor
+    ///
+    /// signature is an ed25519 signature over the atom_tag bytes followed by
+    /// the coherence_score byte. The public key is stored with the ATOM so the
+    /// attestation can later be checked against the contributor's known key.
+    #[payable]
+    pub fn record_self_attested_atom(
+        &mut self,
+        atom: ATOMOnChain,
+        signature: Base64VecU8,
+        public_key: Base64VecU8,
+    ) -> String {
+        let signature: [u8; 64] = signature
+            .0
+            .as_slice()
+            .try_into()
+            .expect("Signature must be 64 bytes");
+        let key: [u8; 32] = public_key
+            .0
+            .as_slice()
+            .try_into()
+            .expect("Public key must be 32 bytes");
 
-        // Return transaction hash equivalent
-        format!("{}:{}", env::block_height(), atom_tag)
+        let mut message = atom.atom_tag.as_bytes().to_vec();
+        message.push(atom.coherence_score);
+        assert!(
+            env::ed25519_verify(&signature, &message, &key),
+            "Invalid self-attestation signature"
+        );
+
+        let atom_tag = atom.atom_tag.clone();
+        let result = self.internal_record_atom(atom, None);
+        self.attestation_keys.insert(&atom_tag, &public_key.0);
+        result
+    }
+
+    /// Record an ATOM, naming its repo if this ATOM creates it
+    ///
+    /// display_name is ignored for repos that already exist; use
+    /// set_repo_display_name to rename those.
+    #[payable]
+    pub fn record_atom_with_repo_meta(
+        &mut self,
+        atom: ATOMOnChain,
+        display_name: Option<String>,
+    ) -> String {
+        let repo = normalize_repo(&atom.repo);
+        let is_new_repo = self.repos.get(&repo).is_none();
+
+        let result = self.internal_record_atom(atom, None);
+
+        if let (true, Some(name)) = (is_new_repo, display_name) {
+            let mut state = self.repos.get(&repo).expect("Repo state missing after record");
+            state.display_name = Some(name);
+            self.repos.insert(&repo, &state);
+        }
+        result
     }
 
     /// Batch record multiple ATOMs (gas efficient)
     #[payable]
     pub fn batch_record_atoms(&mut self, atoms: Vec<ATOMOnChain>) -> Vec<String> {
+        // Fail before any work rather than running out of gas mid-batch
+        assert!(
+            atoms.len() <= MAX_BATCH_SIZE,
+            "Batch of {} ATOMs exceeds max batch size {}",
+            atoms.len(),
+            MAX_BATCH_SIZE
+        );
+        self.assert_capacity_for(atoms.len() as u64);
         atoms
             .into_iter()
             .map(|atom| self.record_atom(atom))
@@ -134,221 +696,4320 @@ impl SpiralSafeVortex {
     }
 
     /// Update coherence for a repo (governance only)
+    ///
+    /// Rebases total_coherence to coherence * atom_count so the override is
+    /// the starting point for subsequent ATOMs rather than being discarded
+    /// by the next recomputed average.
     pub fn update_coherence(&mut self, repo: String, coherence: u8) {
+        let repo = normalize_repo(&repo);
         assert_eq!(
             env::predecessor_account_id(),
             self.owner,
             "Only owner can update coherence directly"
         );
-
-        if let Some(mut state) = self.repos.get(&repo) {
-            state.average_coherence = coherence;
-            self.repos.insert(&repo, &state);
-        }
+        assert!(coherence <= 100, "Invalid coherence score");
+        self.rebase_repo_coherence(&repo, coherence);
     }
 
-    /// Set snap-in threshold (governance only)
-    pub fn set_snap_in_threshold(&mut self, threshold: u8) {
+    /// Update coherence for many repos in one call (governance only)
+    ///
+    /// Each update is applied as update_coherence would; returns the repos
+    /// that were not found and so were skipped.
+    pub fn batch_update_coherence(&mut self, updates: Vec<(String, u8)>) -> Vec<String> {
         assert_eq!(
             env::predecessor_account_id(),
             self.owner,
-            "Only owner"
+            "Only owner can update coherence directly"
+        );
+        assert!(
+            updates.len() <= MAX_BATCH_SIZE,
+            "Batch of {} updates exceeds max batch size {}",
+            updates.len(),
+            MAX_BATCH_SIZE
+        );
+        assert!(
+            updates.iter().all(|(_, coherence)| *coherence <= 100),
+            "Invalid coherence score"
         );
-        assert!(threshold <= 100, "Invalid threshold");
-        self.snap_in_threshold = threshold;
-    }
-
-    // ==================== VIEW METHODS ====================
 
-    /// Get a single ATOM by tag
-    pub fn get_atom(&self, atom_tag: String) -> Option<ATOMOnChain> {
-        self.atoms.get(&atom_tag)
+        updates
+            .into_iter()
+            .filter_map(|(repo, coherence)| {
+                let repo = normalize_repo(&repo);
+                (!self.rebase_repo_coherence(&repo, coherence)).then_some(repo)
+            })
+            .collect()
     }
 
-    /// Get vortex ecosystem state
-    pub fn get_vortex_state(&self) -> VortexState {
-        self.vortex_state.clone()
-    }
+    /// Set a repo's display name (governance only)
+    pub fn set_repo_display_name(&mut self, repo: String, display_name: Option<String>) {
+        let repo = normalize_repo(&repo);
+        self.assert_owner();
 
-    /// Get repo state
-    pub fn get_repo_state(&self, repo: String) -> Option<RepoState> {
-        self.repos.get(&repo)
+        let mut state = self.repos.get(&repo).expect("Repo not found");
+        state.display_name = display_name;
+        self.repos.insert(&repo, &state);
     }
 
-    /// Get all repo coherence scores
-    pub fn get_repo_coherence(&self) -> Vec<(String, u8)> {
-        self.repos
-            .iter()
-            .map(|(repo, state)| (repo, state.average_coherence))
-            .collect()
-    }
+    /// Mark an ATOM as verified (governance only)
+    pub fn verify_atom(&mut self, atom_tag: String) {
+        assert_eq!(
+            env::predecessor_account_id(),
+            self.owner,
+            "Only owner can verify ATOMs"
+        );
 
-    /// Get ATOMs for a repo
-    pub fn get_repo_atoms(&self, repo: String, limit: u32) -> Vec<ATOMOnChain> {
-        // This is simplified - production would use pagination
-        let mut result = Vec::new();
-        for (_, atom) in self.atoms.iter() {
-            if atom.repo == repo && result.len() < limit as usize {
-                result.push(atom);
-            }
-        }
-        result
-    }
+        let mut atom = self.atoms.get(&atom_tag).expect("ATOM not found");
+        atom.verified = true;
+        self.atoms.insert(&atom_tag, &atom);
 
-    /// Get ATOMs for a contributor
-    pub fn get_contributor_atoms(&self, contributor: String) -> Vec<ATOMOnChain> {
-        if let Some(tags) = self.contributor_atoms.get(&contributor) {
-            tags.iter()
-                .filter_map(|tag| self.atoms.get(&tag))
-                .collect()
-        } else {
-            Vec::new()
+        // A held snap-in reward becomes claimable once governance vouches for the ATOM
+        if let Some(amount) = self.pending_rewards.remove(&atom_tag) {
+            if let Ok(receiver) = atom.contributor.parse::<AccountId>() {
+                self.credit_reward(&receiver, amount);
+                self.released_rewards.insert(&atom_tag, &amount);
+            }
         }
     }
 
-    /// Check if ecosystem has achieved snap-in
-    pub fn check_ecosystem_snap_in(&self) -> (bool, u8) {
-        let snap_in = self.vortex_state.average_coherence >= self.snap_in_threshold;
-        (snap_in, self.vortex_state.average_coherence)
-    }
-
-    /// Get H&&S attribution for a contributor
-    pub fn get_attribution(&self, contributor: String) -> (u64, u8, Vec<String>) {
-        if let Some(tags) = self.contributor_atoms.get(&contributor) {
-            let atoms: Vec<ATOMOnChain> = tags
-                .iter()
-                .filter_map(|tag| self.atoms.get(&tag))
-                .collect();
+    /// Correct an ATOM, keeping the replaced version in its history (uploader or owner)
+    ///
+    /// The amendment cannot move the ATOM to another repo or contributor, or
+    /// below the recording minimum that applies to new ATOMs. Sequence, block
+    /// height and uploader are kept from the original, and verification is
+    /// cleared so the corrected record is reviewed again.
+    ///
+    /// Coherence totals, phase counts, the contributor's snap_in_count and
+    /// any snap-in reward still held for verification follow the amendment.
+    /// The repo's weighted average and the ecosystem snap_in_count are left
+    /// as recorded, as are the marker index entries for markers the
+    /// amendment drops.
+    pub fn amend_atom(&mut self, atom_tag: String, updated: ATOMOnChain) {
+        let mut updated = updated;
+        let previous = self.atoms.get(&atom_tag).expect("ATOM not found");
+        let caller = env::predecessor_account_id();
+        assert!(
+            caller == self.owner || previous.recorded_by.as_ref() == Some(&caller),
+            "Only the uploader or owner can amend an ATOM"
+        );
+        assert_eq!(updated.atom_tag, atom_tag, "ATOM tag cannot be amended");
+        updated.repo = normalize_repo(&updated.repo);
+        assert!(
+            updated.repo == previous.repo && updated.contributor == previous.contributor,
+            "Amendments cannot move an ATOM between repos or contributors"
+        );
+        assert!(updated.coherence_score <= self.max_coherence, "Invalid coherence score");
+        self.assert_meets_record_minimum(&updated);
+        assert_atom_field_lengths(&updated);
+        self.assert_valid_commit_hash(&updated.commit_hash);
 
-            let count = atoms.len() as u64;
-            let avg_coherence = if count > 0 {
-                (atoms.iter().map(|a| a.coherence_score as u64).sum::<u64>() / count) as u8
-            } else {
-                0
-            };
+        updated.markers = dedup_preserving_order(updated.markers);
+        updated.phases_passed = dedup_preserving_order(updated.phases_passed);
+        self.assert_valid_phases(&updated.phases_passed);
+        updated.sequence = previous.sequence;
+        updated.block_height = previous.block_height;
+        updated.recorded_by = previous.recorded_by.clone();
+        updated.invalid = previous.invalid;
+        updated.verified = false;
 
-            let all_markers: Vec<String> = atoms
-                .iter()
-                .flat_map(|a| a.markers.clone())
-                .collect();
+        let added_markers: Vec<String> = updated
+            .markers
+            .iter()
+            .filter(|marker| !previous.markers.contains(marker))
+            .cloned()
+            .collect();
+        self.add_to_marker_index(&atom_tag, &added_markers);
+        self.apply_phase_delta(&previous, &updated);
 
-            (count, avg_coherence, all_markers)
-        } else {
-            (0, 0, Vec::new())
+        // Invalidated ATOMs are already out of the averages
+        if !previous.invalid {
+            let old_coherence = self.coherence_percent(previous.coherence_score) as u64;
+            let new_coherence = self.coherence_percent(updated.coherence_score) as u64;
+            self.apply_coherence_delta(&previous, old_coherence, new_coherence);
+            self.rehold_snap_in_reward(&updated);
         }
-    }
-
-    // ==================== INTERNAL METHODS ====================
 
-    fn update_repo_state(&mut self, atom: &ATOMOnChain) {
-        let mut state = self.repos.get(&atom.repo).unwrap_or(RepoState {
-            repo: atom.repo.clone(),
-            atom_count: 0,
-            total_coherence: 0,
-            average_coherence: 0,
-            last_snap_in: None,
+        let mut history = self.atom_history.get(&atom_tag).unwrap_or_else(|| {
+            Vector::new(StorageKey::AtomVersions {
+                atom_tag_hash: env::sha256(atom_tag.as_bytes()),
+            })
         });
+        history.push(&previous);
+        self.atom_history.insert(&atom_tag, &history);
 
-        state.atom_count += 1;
-        state.total_coherence += atom.coherence_score as u64;
-        state.average_coherence =
-            (state.total_coherence / state.atom_count) as u8;
+        self.atoms.insert(&atom_tag, &updated);
+        env::log_str(&format!("AMENDED: {}", atom_tag));
+    }
 
-        if atom.coherence_score >= self.snap_in_threshold {
-            state.last_snap_in = Some(atom.timestamp.clone());
+    /// Flag a contested ATOM (uploader or owner)
+    ///
+    /// The ATOM stays in storage and in all averages; the flag is only surfaced.
+    pub fn flag_atom(&mut self, atom_tag: String, reason: String) {
+        let atom = self.atoms.get(&atom_tag).expect("ATOM not found");
+        let caller = env::predecessor_account_id();
+        assert!(
+            caller == self.owner || atom.recorded_by.as_ref() == Some(&caller),
+            "Only the uploader or owner can flag an ATOM"
+        );
+        if let Some(existing) = self.flags.get(&atom_tag) {
+            assert!(existing.resolved, "ATOM already flagged");
         }
 
-        self.repos.insert(&atom.repo, &state);
+        self.flags.insert(
+            &atom_tag,
+            &FlagRecord {
+                flagged_by: caller,
+                reason,
+                timestamp: env::block_timestamp().to_string(),
+                resolved: false,
+            },
+        );
+        env::log_str(&format!("FLAGGED: {}", atom_tag));
     }
 
-    fn add_to_contributor_trail(&mut self, atom: &ATOMOnChain) {
-        let mut trail = self
-            .contributor_atoms
-            .get(&atom.contributor)
-            .unwrap_or_else(|| Vector::new(atom.contributor.as_bytes()));
+    /// Resolve an ATOM's flag (governance only)
+    pub fn resolve_flag(&mut self, atom_tag: String) {
+        assert_eq!(
+            env::predecessor_account_id(),
+            self.owner,
+            "Only owner can resolve flags"
+        );
 
-        trail.push(&atom.atom_tag);
-        self.contributor_atoms.insert(&atom.contributor, &trail);
+        let mut flag = self.flags.get(&atom_tag).expect("ATOM not flagged");
+        assert!(!flag.resolved, "Flag already resolved");
+        flag.resolved = true;
+        self.flags.insert(&atom_tag, &flag);
     }
 
-    fn update_vortex_state(&mut self, atom: &ATOMOnChain) {
-        let prev_total = self.vortex_state.total_atoms as u64
-            * self.vortex_state.average_coherence as u64;
+    /// Exclude a flagged ATOM from repo, contributor and ecosystem averages (uploader or owner)
+    ///
+    /// The ATOM stays in storage for audit, marked invalid, and keeps counting
+    /// toward max_total_atoms. The contributor and ecosystem snap_in_count
+    /// drop if the ATOM clears the repo's current threshold. The repo's
+    /// weighted average and coherence series keep the ATOM as recorded.
+    pub fn invalidate_atom(&mut self, atom_tag: String) {
+        let mut atom = self.atoms.get(&atom_tag).expect("ATOM not found");
+        let caller = env::predecessor_account_id();
+        assert!(
+            caller == self.owner || atom.recorded_by.as_ref() == Some(&caller),
+            "Only the uploader or owner can invalidate an ATOM"
+        );
+        assert!(self.flags.get(&atom_tag).is_some(), "ATOM must be flagged first");
 
-        self.vortex_state.total_atoms += 1;
+        assert!(!atom.invalid, "ATOM already invalidated");
+        atom.invalid = true;
+        self.atoms.insert(&atom_tag, &atom);
+        self.pending_rewards.remove(&atom_tag);
 
-        let new_avg = (prev_total + atom.coherence_score as u64)
-            / self.vortex_state.total_atoms as u64;
+        let coherence = self.coherence_percent(atom.coherence_score) as u64;
+        let snapped = coherence >= self.effective_threshold(&atom.repo) as u64;
 
-        self.vortex_state.average_coherence = new_avg as u8;
+        if let Some(mut state) = self.repos.get(&atom.repo) {
+            state.atom_count = state.atom_count.saturating_sub(1);
+            state.total_coherence = state.total_coherence.saturating_sub(coherence);
+            state.average_coherence =
+                safe_average(state.total_coherence as u128, state.atom_count);
+            self.repos.insert(&atom.repo, &state);
+        }
+
+        let contributor = self.canonical_contributor(&atom.contributor);
+        if let Some(mut stats) = self.contributor_stats.get(&contributor) {
+            stats.atom_count = stats.atom_count.saturating_sub(1);
+            stats.coherence_sum = stats.coherence_sum.saturating_sub(coherence);
+            if snapped {
+                stats.snap_in_count = stats.snap_in_count.saturating_sub(1);
+            }
+            self.contributor_stats.insert(&contributor, &stats);
+        }
+
+        let vortex = &mut self.vortex_state;
+        vortex.total_atoms = vortex.total_atoms.saturating_sub(1);
+        vortex.total_coherence_sum = vortex.total_coherence_sum.saturating_sub(coherence);
+        vortex.average_coherence =
+            safe_average(vortex.total_coherence_sum as u128, vortex.total_atoms);
+        if snapped {
+            vortex.snap_in_count = vortex.snap_in_count.saturating_sub(1);
+        }
+        vortex.last_update = env::block_timestamp().to_string();
+
+        env::log_str(&format!("INVALIDATED: {}", atom_tag));
+    }
+
+    /// Link an alias to a canonical contributor identity (governance only)
+    ///
+    /// New ATOMs under the alias are trailed under the canonical identity,
+    /// and contributor views aggregate the alias's earlier trail as well.
+    /// The alias's stats and repo memberships fold into the canonical
+    /// identity, so it counts once everywhere. Panics when the alias's trail
+    /// is longer than max_atoms.
+    pub fn link_contributor(&mut self, alias: String, canonical: String, max_atoms: u32) {
+        assert_eq!(
+            env::predecessor_account_id(),
+            self.owner,
+            "Only owner can link contributors"
+        );
+        assert_ne!(alias, canonical, "Cannot link a contributor to itself");
+        assert!(!self.contributor_links.contains_key(&alias), "Alias is already linked");
+        assert!(
+            !self.contributor_links.contains_key(&canonical),
+            "Canonical identity cannot itself be an alias"
+        );
+        assert!(
+            !self.contributor_aliases.contains_key(&alias),
+            "Alias already has aliases of its own"
+        );
+
+        let alias_trail = self.contributor_atoms.get(&alias);
+        let alias_len = alias_trail.as_ref().map(|trail| trail.len()).unwrap_or(0);
+        assert!(
+            alias_len <= max_atoms as u64,
+            "Alias trail has {} ATOMs, exceeds max_atoms",
+            alias_len
+        );
+        // Both identities were counted as contributors when they started their trails
+        if alias_trail.is_some() && self.has_linked_trail(&canonical) {
+            self.vortex_state.contributor_count =
+                self.vortex_state.contributor_count.saturating_sub(1);
+        }
+
+        self.contributor_links.insert(&alias, &canonical);
+        let mut aliases = self.contributor_aliases.get(&canonical).unwrap_or_default();
+        aliases.push(alias.clone());
+        self.contributor_aliases.insert(&canonical, &aliases);
+
+        if let Some(alias_stats) = self.contributor_stats.remove(&alias) {
+            let mut stats = self.contributor_stats.get(&canonical).unwrap_or(ContributorStats {
+                atom_count: 0,
+                coherence_sum: 0,
+                snap_in_count: 0,
+                first_seen: None,
+                last_seen: None,
+            });
+            stats.atom_count += alias_stats.atom_count;
+            stats.coherence_sum += alias_stats.coherence_sum;
+            stats.snap_in_count += alias_stats.snap_in_count;
+            // ISO 8601 timestamps order as strings
+            stats.first_seen = match (stats.first_seen, alias_stats.first_seen) {
+                (Some(ours), Some(theirs)) => Some(ours.min(theirs)),
+                (ours, theirs) => ours.or(theirs),
+            };
+            stats.last_seen = stats.last_seen.max(alias_stats.last_seen);
+            self.contributor_stats.insert(&canonical, &stats);
+        }
+
+        let alias_repos: Vec<String> = alias_trail
+            .map(|trail| trail.iter().collect::<Vec<String>>())
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|tag| self.atoms.get(tag))
+            .map(|atom| atom.repo)
+            .collect();
+        for repo in dedup_preserving_order(alias_repos) {
+            let Some(mut contributors) = self.repo_contributors.get(&repo) else {
+                continue;
+            };
+            if !contributors.remove(&alias) {
+                continue;
+            }
+            if !contributors.insert(&canonical) {
+                if let Some(mut state) = self.repos.get(&repo) {
+                    state.contributor_count = state.contributor_count.saturating_sub(1);
+                    self.repos.insert(&repo, &state);
+                }
+            }
+            self.repo_contributors.insert(&repo, &contributors);
+        }
+    }
+
+    /// Rebuild a contributor's stats from their trail (governance only)
+    ///
+    /// Panics rather than writing partial stats when the trail is longer than
+    /// max_atoms. Returns whether the stored stats changed.
+    pub fn recompute_contributor(&mut self, contributor: String, max_atoms: u32) -> bool {
+        self.assert_owner();
+
+        let contributor = self.canonical_contributor(&contributor);
+        let trail_len = self.linked_trail_len(&contributor);
+        if trail_len == 0 {
+            return false;
+        }
+        assert!(
+            trail_len <= max_atoms as u64,
+            "Trail has {} ATOMs, exceeds max_atoms",
+            trail_len
+        );
+        let tags = self.linked_trail_tags(&contributor);
+
+        let mut rebuilt = ContributorStats {
+            atom_count: 0,
+            coherence_sum: 0,
+            snap_in_count: 0,
+            first_seen: None,
+            last_seen: None,
+        };
+        // Invalidated ATOMs stay out of the stats, matching invalidate_atom
+        for atom in tags.iter().filter_map(|tag| self.atoms.get(tag)).filter(|a| !a.invalid) {
+            self.apply_atom_to_stats(&mut rebuilt, &atom);
+        }
+
+        let changed = self.contributor_stats.get(&contributor).as_ref() != Some(&rebuilt);
+        if changed {
+            self.contributor_stats.insert(&contributor, &rebuilt);
+        }
+        changed
+    }
+
+    /// Sum the coherence and count of a page of a repo's valid ATOMs (owner only)
+    ///
+    /// A verification tool, not a fixer: summing the pages across calls
+    /// reconstructs the true totals to compare with the stored RepoState.
+    /// It changes no state, but runs as a call so it can be owner-gated.
+    /// Repos overridden with update_coherence are expected to differ.
+    pub fn audit_repo(&mut self, repo: String, from_index: u64, limit: u64) -> (u128, u64) {
+        self.assert_owner();
+        assert!(
+            limit <= MAX_BATCH_SIZE as u64,
+            "Page of {} exceeds max page size {}",
+            limit,
+            MAX_BATCH_SIZE
+        );
+        let repo = normalize_repo(&repo);
+        self.repo_atoms_page(&repo, from_index, limit as u32)
+            .iter()
+            .filter(|atom| !atom.invalid)
+            .fold((0u128, 0u64), |(sum, count), atom| {
+                (sum + self.coherence_percent(atom.coherence_score) as u128, count + 1)
+            })
+    }
+
+    /// Rebuild a repo's totals from its valid ATOMs, a page per call (owner only)
+    ///
+    /// Pages accumulate into scratch state and must be walked in order,
+    /// starting from index 0. The call with `finalize` set must reach the
+    /// end of the index; it overwrites the RepoState totals and shifts the
+    /// ecosystem accumulators by the difference. Returns the next index.
+    pub fn recompute_repo(
+        &mut self,
+        repo: String,
+        from_index: u64,
+        limit: u64,
+        finalize: bool,
+    ) -> u64 {
+        self.assert_owner();
+        assert!(
+            limit <= MAX_BATCH_SIZE as u64,
+            "Page of {} exceeds max page size {}",
+            limit,
+            MAX_BATCH_SIZE
+        );
+        let repo = normalize_repo(&repo);
+        let index_len = self.repo_atoms.get(&repo).map(|index| index.len()).unwrap_or(0);
+
+        let mut scratch = if from_index == 0 {
+            RepoRecompute {
+                next_index: 0,
+                total_coherence: 0,
+                atom_count: 0,
+            }
+        } else {
+            self.repo_recomputes
+                .get(&repo)
+                .expect("Recompute must start from index 0")
+        };
+        assert_eq!(scratch.next_index, from_index, "Recompute pages must be contiguous");
+
+        for atom in self.repo_atoms_page(&repo, from_index, limit as u32) {
+            if !atom.invalid {
+                scratch.total_coherence += self.coherence_percent(atom.coherence_score) as u64;
+                scratch.atom_count += 1;
+            }
+        }
+        scratch.next_index = (from_index + limit).min(index_len);
+        let next_index = scratch.next_index;
+
+        if !finalize {
+            self.repo_recomputes.insert(&repo, &scratch);
+            return next_index;
+        }
+
+        assert_eq!(next_index, index_len, "Recompute has not reached the end of the repo index");
+        self.repo_recomputes.remove(&repo);
+        let mut state = self.repos.get(&repo).expect("Repo not found");
+
+        let vortex = &mut self.vortex_state;
+        vortex.total_coherence_sum =
+            vortex.total_coherence_sum.saturating_sub(state.total_coherence)
+                + scratch.total_coherence;
+        vortex.total_atoms =
+            vortex.total_atoms.saturating_sub(state.atom_count) + scratch.atom_count;
+        vortex.average_coherence =
+            safe_average(vortex.total_coherence_sum as u128, vortex.total_atoms);
+
+        state.total_coherence = scratch.total_coherence;
+        state.atom_count = scratch.atom_count;
+        state.average_coherence = safe_average(state.total_coherence as u128, state.atom_count);
+        self.repos.insert(&repo, &state);
+        env::log_str(&format!("RECOMPUTED: {}", repo));
+        next_index
+    }
+
+    /// Capture a page of repos' current averages as the baseline for get_repo_trend (owner only)
+    ///
+    /// Pages follow the storage order of the repos map. Returns the next index.
+    pub fn snapshot_repo_coherence(&mut self, from_index: u64, limit: u64) -> u64 {
+        self.assert_owner();
+        assert!(
+            limit <= MAX_BATCH_SIZE as u64,
+            "Page of {} exceeds max page size {}",
+            limit,
+            MAX_BATCH_SIZE
+        );
+        let keys = self.repos.keys_as_vector();
+        let end = from_index.saturating_add(limit).min(keys.len());
+        let page: Vec<String> = (from_index..end).filter_map(|i| keys.get(i)).collect();
+        for repo in page {
+            if let Some(mut state) = self.repos.get(&repo) {
+                state.coherence_at_last_snapshot = state.average_coherence;
+                self.repos.insert(&repo, &state);
+            }
+        }
+        end
+    }
+
+    /// Grant an account admin rights over lower-risk governance (owner only)
+    pub fn add_admin(&mut self, account: AccountId) {
+        self.assert_owner();
+        self.admins.insert(&account);
+    }
+
+    /// Revoke an account's admin rights (owner only)
+    pub fn remove_admin(&mut self, account: AccountId) {
+        self.assert_owner();
+        self.admins.remove(&account);
+    }
+
+    /// Sweep surplus contract balance to a receiver (owner only)
+    ///
+    /// The balance left behind must still cover storage staking for the
+    /// contract's current storage usage.
+    pub fn withdraw(&mut self, amount: U128, receiver: AccountId) -> Promise {
+        self.assert_owner();
+        let reserve = env::storage_usage() as u128 * env::storage_byte_cost().as_yoctonear();
+        let balance = env::account_balance().as_yoctonear();
+        assert!(
+            amount.0 <= balance && balance - amount.0 >= reserve,
+            "Withdrawal would drop below the storage reserve of {} yoctoNEAR",
+            reserve
+        );
+        Promise::new(receiver).transfer(NearToken::from_yoctonear(amount.0))
+    }
+
+    /// Recreate a missing stats entry for a contributor from their trail (governance only)
+    ///
+    /// A repair path: recording keeps contributor_stats current, but an
+    /// entry lost to a storage repair cannot be found again by iterating
+    /// trails, which live in a LookupMap. Operators name each affected
+    /// contributor from off-chain records. Returns whether an entry was
+    /// added; existing entries are left as they are (use
+    /// recompute_contributor to rebuild them).
+    pub fn register_contributor(&mut self, contributor: String, max_atoms: u32) -> bool {
+        self.assert_owner();
+        let canonical = self.canonical_contributor(&contributor);
+        if self.contributor_stats.get(&canonical).is_some() {
+            return false;
+        }
+        self.recompute_contributor(canonical, max_atoms)
+    }
+
+    /// Set snap-in threshold (governance only)
+    pub fn set_snap_in_threshold(&mut self, threshold: u8) {
+        self.assert_admin_or_owner();
+        assert!(threshold <= 100, "Invalid threshold");
+        self.snap_in_threshold = threshold;
+    }
+
+    /// Set the snap-in threshold only if it still equals `expected` (governance only)
+    ///
+    /// Lets governance tooling detect a concurrent edit instead of silently
+    /// overwriting it.
+    pub fn set_snap_in_threshold_cas(&mut self, expected: u8, new: u8) {
+        self.assert_admin_or_owner();
+        assert_eq!(
+            self.snap_in_threshold, expected,
+            "Threshold changed concurrently"
+        );
+        assert!(new <= 100, "Invalid threshold");
+        self.snap_in_threshold = new;
+    }
+
+    /// Override the snap-in threshold for one repo, or clear it with None (governance only)
+    pub fn set_repo_snap_in_threshold(&mut self, repo: String, threshold: Option<u8>) {
+        let repo = normalize_repo(&repo);
+        self.assert_admin_or_owner();
+        match threshold {
+            Some(threshold) => {
+                assert!(threshold <= 100, "Invalid threshold");
+                self.repo_thresholds.insert(&repo, &threshold);
+            }
+            None => {
+                self.repo_thresholds.remove(&repo);
+            }
+        }
+    }
+
+    /// Choose how a repo's headline coherence is aggregated (governance only)
+    pub fn set_repo_aggregation_mode(&mut self, repo: String, mode: AggregationMode) {
+        let repo = normalize_repo(&repo);
+        self.assert_admin_or_owner();
+        match mode {
+            AggregationMode::Mean => {
+                self.repo_aggregation_modes.remove(&repo);
+            }
+            AggregationMode::MedianRecent(window) => {
+                assert!(
+                    window > 0 && window <= MAX_AGGREGATION_WINDOW,
+                    "Median window must be 1-{}",
+                    MAX_AGGREGATION_WINDOW
+                );
+                self.repo_aggregation_modes.insert(&repo, &mode);
+            }
+            AggregationMode::Min => {
+                self.repo_aggregation_modes.insert(&repo, &mode);
+            }
+        }
+    }
+
+    /// Set the coherence below which ATOMs are refused (governance only)
+    pub fn set_min_record_coherence(&mut self, minimum: u8) {
+        self.assert_admin_or_owner();
+        assert!(minimum <= 100, "Invalid minimum");
+        self.min_record_coherence = minimum;
+    }
+
+    /// Override the recording minimum for one repo, or clear it with None (governance only)
+    pub fn set_repo_min_record_coherence(&mut self, repo: String, minimum: Option<u8>) {
+        let repo = normalize_repo(&repo);
+        self.assert_admin_or_owner();
+        match minimum {
+            Some(minimum) => {
+                assert!(minimum <= 100, "Invalid minimum");
+                self.repo_min_record_coherence.insert(&repo, &minimum);
+            }
+            None => {
+                self.repo_min_record_coherence.remove(&repo);
+            }
+        }
+    }
+
+    /// Set the weight an ATOM carrying a marker contributes (governance only)
+    pub fn set_marker_weight(&mut self, marker: String, weight: u8) {
+        self.assert_admin_or_owner();
+        self.marker_weights.insert(&marker, &weight);
+    }
+
+    /// Set the allowed phases in pipeline order; an empty list accepts any phase (governance only)
+    pub fn set_phase_registry(&mut self, phases: Vec<String>) {
+        self.assert_admin_or_owner();
+        self.phase_registry = dedup_preserving_order(phases);
+    }
+
+    /// Toggle requiring phases_passed to be a prefix of the registry order (governance only)
+    pub fn set_phase_order_enforced(&mut self, enforced: bool) {
+        self.assert_admin_or_owner();
+        self.phase_order_enforced = enforced;
+    }
+
+    /// Toggle maintaining the marker-weighted repo average (governance only)
+    ///
+    /// Only ATOMs recorded while enabled feed the weighted average; the raw
+    /// average is always maintained.
+    pub fn set_weighted_averages(&mut self, enabled: bool) {
+        self.assert_owner();
+        self.weighted_averages_enabled = enabled;
+    }
+
+    /// Cap the total ATOMs the contract accepts, or lift the cap with None (governance only)
+    pub fn set_max_total_atoms(&mut self, max_total_atoms: Option<u64>) {
+        self.assert_owner();
+        self.max_total_atoms = max_total_atoms;
+    }
+
+    /// Toggle requiring commit hashes to be lowercase hex git hashes (governance only)
+    ///
+    /// Disable for ATOMs sourced from non-git systems.
+    pub fn set_commit_hash_validation(&mut self, enabled: bool) {
+        self.assert_owner();
+        self.validate_commit_hash = enabled;
+    }
+
+    /// Set the standard and version stamped on emitted events (governance only)
+    pub fn set_event_metadata(&mut self, standard: String, version: String) {
+        self.assert_owner();
+        assert!(!standard.is_empty() && !version.is_empty(), "Event standard and version required");
+        self.event_standard = standard;
+        self.event_version = version;
+    }
+
+    /// Set the top of the coherence_score scale (governance only)
+    ///
+    /// Scores are recorded as-is but aggregated as a percentage of this
+    /// maximum, so averages and the snap-in threshold stay on 0-100. The
+    /// scale is fixed once any ATOM has been recorded, since stored totals
+    /// were summed under it.
+    pub fn set_max_coherence(&mut self, max_coherence: u8) {
+        self.assert_owner();
+        assert!(max_coherence > 0, "Invalid max coherence");
+        assert_eq!(
+            self.atom_sequence_counter, 0,
+            "Coherence scale cannot change once ATOMs are recorded"
+        );
+        self.max_coherence = max_coherence;
+    }
+
+    /// Set how many distinct markers earn a full diversity score (governance only)
+    pub fn set_marker_diversity_max(&mut self, max_markers: u8) {
+        self.assert_owner();
+        assert!(max_markers > 0, "Invalid marker diversity max");
+        self.marker_diversity_max = max_markers;
+    }
+
+    /// Set the fungible token paid out on snap-in (governance only)
+    pub fn set_reward_token(&mut self, ft_account: AccountId) {
+        self.assert_owner();
+        self.reward_token = Some(ft_account);
+    }
+
+    /// Set the base reward per snap-in ATOM (governance only)
+    pub fn set_reward_per_snap_in(&mut self, amount: U128) {
+        self.assert_owner();
+        self.reward_per_snap_in = amount.0;
+    }
+
+    /// Set the sibling contract whose ecosystem average folds into ours (governance only)
+    pub fn set_partner_contract(&mut self, account: AccountId) {
+        self.assert_owner();
+        self.partner_contract = Some(account);
+    }
+
+    /// Fetch the partner contract's vortex state for get_combined_coherence (governance only)
+    pub fn sync_partner_coherence(&mut self) -> Promise {
+        self.assert_admin_or_owner();
+        let partner = self.partner_contract.clone().expect("No partner contract configured");
+
+        ext_partner::ext(partner)
+            .with_static_gas(GAS_FOR_PARTNER_VIEW)
+            .get_vortex_state()
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_PARTNER_CALLBACK)
+                    .on_partner_state(),
+            )
+    }
+
+    /// Store the partner's vortex state; a failed fetch keeps the previous one
+    #[private]
+    pub fn on_partner_state(
+        &mut self,
+        #[callback_result] result: Result<VortexState, PromiseError>,
+    ) {
+        match result {
+            Ok(state) => self.partner_state = Some(state),
+            Err(_) => env::log_str("PARTNER SYNC FAILED: keeping previous partner state"),
+        }
+    }
+
+    /// Transfer the caller's claimable snap-in rewards in one ft_transfer
+    pub fn claim_rewards(&mut self) -> Promise {
+        let token = self.reward_token.clone().expect("No reward token configured");
+        let receiver = env::predecessor_account_id();
+        let amount = self.claimable_rewards.remove(&receiver).expect("No rewards to claim");
+
+        ext_ft::ext(token)
+            .with_attached_deposit(NearToken::from_yoctonear(1))
+            .with_static_gas(GAS_FOR_FT_TRANSFER)
+            .ft_transfer(receiver.clone(), U128(amount), Some("SNAP-IN rewards".to_string()))
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_REWARD_CALLBACK)
+                    .on_reward_claim(receiver, U128(amount)),
+            )
+    }
+
+    /// Re-credit a claim whose transfer failed so it can be retried
+    #[private]
+    pub fn on_reward_claim(
+        &mut self,
+        receiver: AccountId,
+        amount: U128,
+        #[callback_result] result: Result<(), PromiseError>,
+    ) {
+        if result.is_err() {
+            env::log_str(&format!("REWARD FAILED: {} to {}", amount.0, receiver));
+            self.credit_reward(&receiver, amount.0);
+        }
+    }
+
+    /// Get the snap-in reward held for an ATOM until it is verified
+    pub fn get_pending_reward(&self, atom_tag: String) -> Option<U128> {
+        self.pending_rewards.get(&atom_tag).map(U128)
+    }
+
+    /// Get the rewards an account can collect with claim_rewards
+    pub fn get_claimable_rewards(&self, account: AccountId) -> U128 {
+        U128(self.claimable_rewards.get(&account).unwrap_or(0))
+    }
+
+    /// Set ecosystem health weights (governance only)
+    pub fn set_health_weights(&mut self, weights: HealthWeights) {
+        self.assert_owner();
+        assert!(
+            weights.coherence as u32 + weights.snap_in as u32 + weights.contributors as u32 > 0,
+            "At least one weight must be non-zero"
+        );
+        assert!(weights.contributor_target > 0, "Contributor target must be positive");
+        self.health_weights = weights;
+    }
+
+    // ==================== VIEW METHODS ====================
+
+    /// Get a single ATOM by tag
+    pub fn get_atom(&self, atom_tag: String) -> Option<ATOMOnChain> {
+        self.atoms.get(&atom_tag)
+    }
+
+    /// Get an ATOM with whether it meets its repo's threshold, the repo average, and any flag
+    pub fn get_atom_detail(&self, atom_tag: String) -> Option<AtomDetail> {
+        let atom = self.atoms.get(&atom_tag)?;
+        let met_threshold =
+            self.coherence_percent(atom.coherence_score) >= self.effective_threshold(&atom.repo);
+        let repo_average_coherence = self
+            .repos
+            .get(&atom.repo)
+            .map(|state| state.average_coherence)
+            .unwrap_or(0);
+
+        Some(AtomDetail {
+            atom,
+            met_threshold,
+            repo_average_coherence,
+            flag: self.flags.get(&atom_tag),
+        })
+    }
+
+    /// Get the account that recorded an ATOM
+    pub fn get_atom_recorder(&self, atom_tag: String) -> Option<AccountId> {
+        self.atoms.get(&atom_tag).and_then(|atom| atom.recorded_by)
+    }
+
+    /// Get the public key an ATOM was self-attested with, if any
+    pub fn get_attestation_key(&self, atom_tag: String) -> Option<Base64VecU8> {
+        self.attestation_keys.get(&atom_tag).map(Base64VecU8::from)
+    }
+
+    /// Get all prior versions of an amended ATOM, oldest first
+    pub fn get_atom_history(&self, atom_tag: String) -> Vec<ATOMOnChain> {
+        if let Some(history) = self.atom_history.get(&atom_tag) {
+            history.iter().collect()
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Get the dispute flag for an ATOM, if any
+    pub fn get_flag(&self, atom_tag: String) -> Option<FlagRecord> {
+        self.flags.get(&atom_tag)
+    }
+
+    /// Get the largest batch accepted by batch_record_atoms
+    pub fn max_batch_size(&self) -> u32 {
+        MAX_BATCH_SIZE as u32
+    }
+
+    /// Get many ATOMs by tag, preserving input order (None for unknown tags)
+    pub fn get_atoms_batch(&self, atom_tags: Vec<String>) -> Vec<Option<ATOMOnChain>> {
+        assert!(
+            atom_tags.len() <= MAX_VIEW_BATCH_SIZE,
+            "Too many tags: max {} per call",
+            MAX_VIEW_BATCH_SIZE
+        );
+        atom_tags.iter().map(|tag| self.atoms.get(tag)).collect()
+    }
+
+    /// Get contract storage usage and its locked cost
+    pub fn get_storage_stats(&self) -> StorageStats {
+        let storage_usage = env::storage_usage();
+        let byte_cost = env::storage_byte_cost().as_yoctonear();
+
+        StorageStats {
+            storage_usage,
+            storage_byte_cost: U128(byte_cost),
+            storage_cost: U128(storage_usage as u128 * byte_cost),
+            total_atoms: self.vortex_state.total_atoms,
+        }
+    }
+
+    /// Get the version stored in state and the version of the deployed code
+    ///
+    /// A mismatch means the code was upgraded without running migrate.
+    pub fn get_version(&self) -> (String, String) {
+        (self.version.clone(), CONTRACT_VERSION.to_string())
+    }
+
+    /// Get the standard and version stamped on emitted events
+    pub fn get_event_metadata(&self) -> (String, String) {
+        (self.event_standard.clone(), self.event_version.clone())
+    }
+
+    /// Get the ecosystem average folded with the synced partner's, weighted by ATOM count
+    pub fn get_combined_coherence(&self) -> u8 {
+        let own = &self.vortex_state;
+        let (partner_sum, partner_atoms) = match &self.partner_state {
+            Some(partner) => (
+                partner.average_coherence as u128 * partner.total_atoms as u128,
+                partner.total_atoms,
+            ),
+            None => (0, 0),
+        };
+        safe_average(
+            own.average_coherence as u128 * own.total_atoms as u128 + partner_sum,
+            own.total_atoms + partner_atoms,
+        )
+    }
+
+    /// Get the number of stored ATOMs, invalidated ones included, and the capacity cap
+    pub fn get_capacity(&self) -> (u64, Option<u64>) {
+        (self.atom_sequence_counter, self.max_total_atoms)
+    }
+
+    /// Get the sequence the next recorded ATOM will receive
+    pub fn get_atom_sequence(&self) -> u64 {
+        self.atom_sequence_counter
+    }
+
+    /// Get the top of the coherence_score scale
+    pub fn get_max_coherence(&self) -> u8 {
+        self.max_coherence
+    }
+
+    /// Get vortex ecosystem state
+    pub fn get_vortex_state(&self) -> VortexState {
+        self.vortex_state.clone()
+    }
+
+    /// Get the ecosystem average after each recent ATOM, oldest first
+    pub fn get_vortex_history(&self) -> Vec<u8> {
+        self.vortex_history.clone()
+    }
+
+    /// Estimate ecosystem coherence atoms_ahead ATOMs from now
+    ///
+    /// A naive linear extrapolation of the momentum across vortex_history,
+    /// clamped to 0-100. Treat it as a forecast, not a guarantee.
+    pub fn project_ecosystem_coherence(&self, atoms_ahead: u64) -> u8 {
+        let current = self.vortex_state.average_coherence as i128;
+        if self.vortex_history.len() < 2 {
+            return current as u8;
+        }
+
+        let first = self.vortex_history[0] as i128;
+        let last = *self.vortex_history.last().unwrap() as i128;
+        let steps = (self.vortex_history.len() - 1) as i128;
+
+        let projected = current + (last - first) * atoms_ahead as i128 / steps;
+        projected.clamp(0, 100) as u8
+    }
+
+    /// Whether an account holds admin rights
+    pub fn is_admin(&self, account: AccountId) -> bool {
+        self.admins.contains(&account)
+    }
+
+    /// Get repo state
+    pub fn get_repo_state(&self, repo: String) -> Option<RepoState> {
+        let repo = normalize_repo(&repo);
+        self.repos.get(&repo)
+    }
+
+    /// Get a repo's current average and its signed change since the last snapshot
+    pub fn get_repo_trend(&self, repo: String) -> (u8, i16) {
+        let repo = normalize_repo(&repo);
+        match self.repos.get(&repo) {
+            Some(state) => (
+                state.average_coherence,
+                state.average_coherence as i16 - state.coherence_at_last_snapshot as i16,
+            ),
+            None => (0, 0),
+        }
+    }
+
+    /// Get the ATOM whose record first lifted a repo's average to its threshold
+    pub fn get_repo_first_snap_in_atom(&self, repo: String) -> Option<ATOMOnChain> {
+        let repo = normalize_repo(&repo);
+        self.repos
+            .get(&repo)
+            .and_then(|state| state.first_snap_in_atom)
+            .and_then(|tag| self.atoms.get(&tag))
+    }
+
+    /// Get all repo coherence scores
+    pub fn get_repo_coherence(&self) -> Vec<(String, u8)> {
+        self.repos
+            .iter()
+            .map(|(repo, state)| (repo, state.average_coherence))
+            .collect()
+    }
+
+    /// Get the weight registered for a marker, if any
+    pub fn get_marker_weight(&self, marker: String) -> Option<u8> {
+        self.marker_weights.get(&marker)
+    }
+
+    /// Get the snap-in threshold that applies to a repo
+    pub fn get_effective_threshold(&self, repo: String) -> u8 {
+        let repo = normalize_repo(&repo);
+        self.effective_threshold(&repo)
+    }
+
+    /// Get the aggregation mode a repo's headline coherence uses
+    pub fn get_repo_aggregation_mode(&self, repo: String) -> AggregationMode {
+        let repo = normalize_repo(&repo);
+        self.repo_aggregation_modes
+            .get(&repo)
+            .unwrap_or(AggregationMode::Mean)
+    }
+
+    /// Get a repo's headline coherence under its aggregation mode
+    ///
+    /// Mean reads the stored average; MedianRecent and Min walk the newest
+    /// entries of the repo index at read time, skipping invalidated ATOMs.
+    pub fn get_repo_coherence_scored(&self, repo: String) -> u8 {
+        let repo = normalize_repo(&repo);
+        let Some(state) = self.repos.get(&repo) else {
+            return 0;
+        };
+        match self.repo_aggregation_modes.get(&repo).unwrap_or(AggregationMode::Mean) {
+            AggregationMode::Mean => state.average_coherence,
+            AggregationMode::MedianRecent(window) => {
+                median(self.recent_valid_coherence(&repo, window))
+            }
+            AggregationMode::Min => self
+                .recent_valid_coherence(&repo, MAX_AGGREGATION_WINDOW)
+                .into_iter()
+                .min()
+                .unwrap_or(0),
+        }
+    }
+
+    /// Get the minimum coherence an ATOM needs to be recorded in a repo
+    pub fn get_min_record_coherence(&self, repo: String) -> u8 {
+        let repo = normalize_repo(&repo);
+        self.effective_min_record_coherence(&repo)
+    }
+
+    /// Get a page of repo states for a ranking table
+    ///
+    /// Pages follow the stable storage order of the repos map; clients sort
+    /// the collected rows by average_coherence themselves.
+    pub fn get_repo_ranking(&self, from_index: u64, limit: u64) -> Vec<RepoState> {
+        let states = self.repos.values_as_vector();
+        (from_index..states.len())
+            .take(limit as usize)
+            .filter_map(|i| states.get(i))
+            .collect()
+    }
+
+    /// Get repos whose average is below their effective snap-in threshold
+    pub fn get_repos_below_threshold(&self) -> Vec<String> {
+        self.repos
+            .iter()
+            .filter(|(repo, state)| state.average_coherence < self.effective_threshold(repo))
+            .map(|(repo, _)| repo)
+            .collect()
+    }
+
+    /// Get a repo's average after each recent ATOM, oldest first
+    pub fn get_coherence_series(&self, repo: String) -> Vec<u8> {
+        let repo = normalize_repo(&repo);
+        self.coherence_series.get(&repo).unwrap_or_default()
+    }
+
+    /// Get the top repos by coherence gained over their last window series points
+    ///
+    /// Scans at most max_repos repos and only returns positive deltas,
+    /// largest first.
+    pub fn get_most_improved_repos(
+        &self,
+        window: u32,
+        limit: u32,
+        max_repos: u32,
+    ) -> Vec<(String, i16)> {
+        let mut improved: Vec<(String, i16)> = self
+            .repos
+            .keys()
+            .take(max_repos as usize)
+            .filter_map(|repo| {
+                let series = self.coherence_series.get(&repo)?;
+                let last = *series.last()?;
+                let start = series.len().saturating_sub((window as usize).saturating_add(1));
+                let delta = last as i16 - series[start] as i16;
+                (delta > 0).then_some((repo, delta))
+            })
+            .collect();
+
+        improved.sort_by_key(|(_, delta)| std::cmp::Reverse(*delta));
+        improved.truncate(limit as usize);
+        improved
+    }
+
+    /// Get the top contributors by average coherence, with their ATOM counts
+    ///
+    /// Scans at most max_contributors entries of contributor_stats, so
+    /// contributors not yet registered there are absent.
+    pub fn get_contributor_leaderboard(
+        &self,
+        limit: u32,
+        max_contributors: u32,
+    ) -> Vec<(String, u8, u64)> {
+        let mut leaders: Vec<(String, u8, u64)> = self
+            .contributor_stats
+            .iter()
+            .take(max_contributors as usize)
+            .filter(|(_, stats)| stats.atom_count > 0)
+            .map(|(contributor, stats)| {
+                let average = safe_average(stats.coherence_sum as u128, stats.atom_count);
+                (contributor, average, stats.atom_count)
+            })
+            .collect();
+
+        leaders.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        leaders.truncate(limit as usize);
+        leaders
+    }
+
+    /// Get each repo's share of ecosystem coherence as a 0-100 weight
+    pub fn get_repo_weights(&self) -> Vec<(String, u8)> {
+        let total = self.vortex_state.total_coherence_sum;
+        self.repos
+            .iter()
+            .map(|(repo, state)| {
+                let weight = (state.total_coherence * 100).checked_div(total).unwrap_or(0) as u8;
+                (repo, weight)
+            })
+            .collect()
+    }
+
+    /// Get a 0-100 Gini index of ATOM counts across up to max_repos repos
+    ///
+    /// 0 means ATOMs are spread evenly; 100 means one repo holds them all.
+    /// The raw Gini is normalized by its maximum of (n - 1) / n.
+    pub fn get_repo_atom_gini(&self, max_repos: u32) -> u8 {
+        let mut counts: Vec<u64> = self
+            .repos
+            .iter()
+            .take(max_repos as usize)
+            .map(|(_, state)| state.atom_count)
+            .collect();
+        counts.sort_unstable();
+
+        let n = counts.len() as i128;
+        let total: i128 = counts.iter().map(|&c| c as i128).sum();
+        if n < 2 || total == 0 {
+            return 0;
+        }
+
+        let weighted: i128 = counts
+            .iter()
+            .enumerate()
+            .map(|(i, &c)| (2 * i as i128 - n + 1) * c as i128)
+            .sum();
+
+        (weighted * 100 / ((n - 1) * total)).clamp(0, 100) as u8
+    }
+
+    /// Get the allowed phases in pipeline order
+    pub fn get_phase_registry(&self) -> Vec<String> {
+        self.phase_registry.clone()
+    }
+
+    /// Get ecosystem-wide count of ATOMs passing each phase
+    pub fn get_phase_stats(&self) -> Vec<(String, u64)> {
+        self.phase_counts.iter().collect()
+    }
+
+    /// Get count of a repo's ATOMs passing each phase
+    pub fn get_repo_phase_stats(&self, repo: String) -> Vec<(String, u64)> {
+        let repo = normalize_repo(&repo);
+        if let Some(counts) = self.repo_phase_counts.get(&repo) {
+            counts.iter().collect()
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Get ATOM summaries for a repo, as get_repo_atoms without markers and phases
+    pub fn get_repo_atom_summaries(
+        &self,
+        repo: String,
+        limit: u32,
+        exclude_invalid: Option<bool>,
+    ) -> Vec<AtomSummary> {
+        self.get_repo_atoms(repo, limit, exclude_invalid)
+            .into_iter()
+            .map(AtomSummary::from)
+            .collect()
+    }
+
+    /// Get ATOMs for a repo
+    pub fn get_repo_atoms(
+        &self,
+        repo: String,
+        limit: u32,
+        exclude_invalid: Option<bool>,
+    ) -> Vec<ATOMOnChain> {
+        let repo = normalize_repo(&repo);
+        let atoms = self.repo_atoms_page(&repo, 0, limit);
+        if exclude_invalid.unwrap_or(false) {
+            atoms.into_iter().filter(|atom| !atom.invalid).collect()
+        } else {
+            atoms
+        }
+    }
+
+    /// Get average coherence of a repo's unexpired, valid ATOMs among its first max_atoms
+    pub fn get_repo_valid_coherence(&self, repo: String, max_atoms: u32) -> u8 {
+        let repo = normalize_repo(&repo);
+        let now = env::block_timestamp();
+        let scores: Vec<u64> = self
+            .repo_atoms_page(&repo, 0, max_atoms)
+            .iter()
+            .filter(|atom| !atom.invalid && !Self::is_expired(atom, now))
+            .map(|atom| self.coherence_percent(atom.coherence_score) as u64)
+            .collect();
+
+        safe_average(scores.iter().sum::<u64>() as u128, scores.len() as u64)
+    }
+
+    /// Check whether an ATOM's certification has expired
+    pub fn is_atom_expired(&self, atom_tag: String) -> bool {
+        self.atoms
+            .get(&atom_tag)
+            .map(|atom| Self::is_expired(&atom, env::block_timestamp()))
+            .unwrap_or(false)
+    }
+
+    /// Get a repo's ATOMs recorded within an inclusive block height range
+    pub fn get_atoms_in_height_range(
+        &self,
+        repo: String,
+        from_height: u64,
+        to_height: u64,
+        limit: u64,
+    ) -> Vec<ATOMOnChain> {
+        let repo = normalize_repo(&repo);
+        let mut result = Vec::new();
+        if let Some(index) = self.repo_atoms.get(&repo) {
+            for atom in index.iter().filter_map(|tag| self.atoms.get(&tag)) {
+                // Index is in record order, so heights only increase
+                if atom.block_height > to_height || result.len() as u64 >= limit {
+                    break;
+                }
+                if atom.block_height >= from_height {
+                    result.push(atom);
+                }
+            }
+        }
+        result
+    }
+
+    /// Get unverified ATOMs within a page of a repo's index (review queue)
+    ///
+    /// The page covers index positions from_index..from_index + limit, so a
+    /// client walks the queue by advancing from_index by limit each call.
+    pub fn get_unverified_atoms(
+        &self,
+        repo: String,
+        from_index: u64,
+        limit: u32,
+    ) -> Vec<ATOMOnChain> {
+        let repo = normalize_repo(&repo);
+        self.repo_atoms_page(&repo, from_index, limit)
+            .into_iter()
+            .filter(|atom| !atom.verified)
+            .collect()
+    }
+
+    /// Get ATOMs for a contributor, across all linked aliases
+    pub fn get_contributor_atoms(&self, contributor: String) -> Vec<ATOMOnChain> {
+        self.linked_trail_atoms(&contributor)
+    }
+
+    /// Get up to `limit` of a contributor's newest ATOMs across all linked aliases, newest first
+    ///
+    /// Reads up to `limit` tags from the tail of each linked trail and orders
+    /// them by sequence, so the cost is bounded by `limit` per trail rather
+    /// than by the trail lengths.
+    pub fn get_contributor_atoms_recent(
+        &self,
+        contributor: String,
+        limit: u64,
+    ) -> Vec<ATOMOnChain> {
+        let mut atoms: Vec<ATOMOnChain> = self
+            .linked_identities(&contributor)
+            .iter()
+            .filter_map(|identity| self.contributor_atoms.get(identity))
+            .flat_map(|trail| {
+                let len = trail.len();
+                (len.saturating_sub(limit)..len).filter_map(move |index| trail.get(index))
+            })
+            .filter_map(|tag| self.atoms.get(&tag))
+            .collect();
+
+        atoms.sort_by_key(|atom| std::cmp::Reverse(atom.sequence));
+        atoms.truncate(limit as usize);
+        atoms
+    }
+
+    /// Check if ecosystem has achieved snap-in
+    pub fn check_ecosystem_snap_in(&self) -> (bool, u8) {
+        let snap_in = self.vortex_state.average_coherence >= self.snap_in_threshold;
+        (snap_in, self.vortex_state.average_coherence)
+    }
+
+    /// Get ecosystem health weights
+    pub fn get_health_weights(&self) -> HealthWeights {
+        self.health_weights.clone()
+    }
+
+    /// Get ecosystem health as a single 0-100 composite score
+    ///
+    /// health = (w_coherence * average_coherence
+    ///         + w_snap_in * snap_in_ratio
+    ///         + w_contributors * contributor_score)
+    ///         / (w_coherence + w_snap_in + w_contributors)
+    ///
+    /// where snap_in_ratio = snap_in_count * 100 / total_atoms and
+    /// contributor_score = min(contributor_count, contributor_target) * 100 / contributor_target.
+    pub fn get_ecosystem_health(&self) -> u8 {
+        let weights = &self.health_weights;
+        let state = &self.vortex_state;
+
+        let snap_in_ratio =
+            (state.snap_in_count * 100).checked_div(state.total_atoms).unwrap_or(0).min(100);
+        let contributor_score = state.contributor_count.min(weights.contributor_target) * 100
+            / weights.contributor_target;
+
+        let weight_sum =
+            weights.coherence as u64 + weights.snap_in as u64 + weights.contributors as u64;
+        let weighted = weights.coherence as u64 * state.average_coherence as u64
+            + weights.snap_in as u64 * snap_in_ratio
+            + weights.contributors as u64 * contributor_score;
+
+        (weighted / weight_sum).min(100) as u8
+    }
+
+    /// Get aggregate stats for a contributor's canonical identity
+    pub fn get_contributor_stats(&self, contributor: String) -> Option<ContributorStats> {
+        self.contributor_stats.get(&self.canonical_contributor(&contributor))
+    }
+
+    /// Get contributor counts per average-coherence band: [Low, Medium, Snapped]
+    ///
+    /// Low is below 50, Snapped is at or above the ecosystem snap-in
+    /// threshold, and Medium is everything in between.
+    pub fn get_contributor_band_histogram(&self) -> [u64; 3] {
+        let mut bands = [0u64; 3];
+        for (_, stats) in self.contributor_stats.iter() {
+            if stats.atom_count == 0 {
+                continue;
+            }
+            let average = safe_average(stats.coherence_sum as u128, stats.atom_count);
+            let band = if average >= self.snap_in_threshold {
+                2
+            } else if average >= LOW_BAND_CEILING {
+                1
+            } else {
+                0
+            };
+            bands[band] += 1;
+        }
+        bands
+    }
+
+    /// Get H&&S attribution for a contributor, across all linked aliases
+    pub fn get_attribution(&self, contributor: String) -> (u64, u8, Vec<String>) {
+        let atoms = self.linked_trail_atoms(&contributor);
+
+        let count = atoms.len() as u64;
+        let coherence_sum: u64 = atoms
+            .iter()
+            .map(|a| self.coherence_percent(a.coherence_score) as u64)
+            .sum();
+        let avg_coherence = safe_average(coherence_sum as u128, count);
+
+        let all_markers: Vec<String> = atoms
+            .iter()
+            .flat_map(|a| a.markers.clone())
+            .collect();
+
+        (count, avg_coherence, all_markers)
+    }
+
+    /// Get each distinct marker across a contributor's ATOMs with its count, most used first
+    pub fn get_attribution_marker_counts(&self, contributor: String) -> Vec<(String, u64)> {
+        let mut counts: HashMap<String, u64> = HashMap::new();
+        for atom in self.linked_trail_atoms(&contributor) {
+            for marker in atom.markers {
+                *counts.entry(marker).or_insert(0) += 1;
+            }
+        }
+
+        let mut counts: Vec<(String, u64)> = counts.into_iter().collect();
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        counts
+    }
+
+    /// Get count and average coherence of a contributor's ATOMs carrying a marker
+    pub fn get_attribution_by_marker(&self, contributor: String, marker: String) -> (u64, u8) {
+        let scores: Vec<u64> = self
+            .linked_trail_atoms(&contributor)
+            .iter()
+            .filter(|a| a.markers.contains(&marker))
+            .map(|a| self.coherence_percent(a.coherence_score) as u64)
+            .collect();
+
+        let count = scores.len() as u64;
+        let avg_coherence = safe_average(scores.iter().sum::<u64>() as u128, count);
+
+        (count, avg_coherence)
+    }
+
+    /// Get a page of ATOM summaries from the ecosystem-wide feed, newest first
+    pub fn get_recent_atom_summaries(&self, from_end: u64, limit: u64) -> Vec<AtomSummary> {
+        self.get_recent_atoms(from_end, limit)
+            .into_iter()
+            .map(AtomSummary::from)
+            .collect()
+    }
+
+    /// Get a page of the ecosystem-wide feed, newest first
+    ///
+    /// Skips the `from_end` newest ATOMs. Entries reflect each ATOM's current
+    /// version, so amendments and invalidations show up in place.
+    pub fn get_recent_atoms(&self, from_end: u64, limit: u64) -> Vec<ATOMOnChain> {
+        let end = self.atom_feed.len().saturating_sub(from_end);
+        (end.saturating_sub(limit)..end)
+            .rev()
+            .filter_map(|index| self.atom_feed.get(index))
+            .filter_map(|tag| self.atoms.get(&tag))
+            .collect()
+    }
+
+    /// Get a page of ATOMs across all repos carrying a marker, in index order
+    ///
+    /// Pages walk the marker index, which lists ATOMs in record order and
+    /// then, for markers added by amend_atom, in amendment order. ATOMs since
+    /// amended to drop the marker are skipped, so a page may hold fewer than
+    /// `limit` entries.
+    pub fn get_atoms_by_marker(
+        &self,
+        marker: String,
+        from_index: u64,
+        limit: u64,
+    ) -> Vec<ATOMOnChain> {
+        let Some(index) = self.marker_atoms.get(&marker) else {
+            return Vec::new();
+        };
+        (from_index..index.len())
+            .take(limit as usize)
+            .filter_map(|i| index.get(i))
+            .filter_map(|tag| self.atoms.get(&tag))
+            .filter(|atom| atom.markers.contains(&marker))
+            .collect()
+    }
+
+    /// Get distinct repos where a contributor has a valid at-or-above-threshold ATOM
+    pub fn get_contributor_snap_in_repos(&self, contributor: String) -> Vec<String> {
+        let repos: Vec<String> = self
+            .linked_trail_atoms(&contributor)
+            .into_iter()
+            .filter(|a| !a.invalid)
+            .filter(|a| {
+                self.coherence_percent(a.coherence_score) >= self.effective_threshold(&a.repo)
+            })
+            .map(|a| a.repo)
+            .collect();
+
+        dedup_preserving_order(repos)
+    }
+
+    /// Get a contributor's distinct marker count scaled to 0-100
+    pub fn get_contributor_marker_diversity(&self, contributor: String) -> u8 {
+        let distinct: HashSet<String> = self
+            .linked_trail_atoms(&contributor)
+            .into_iter()
+            .flat_map(|a| a.markers)
+            .collect();
+
+        (distinct.len() as u64 * 100 / self.marker_diversity_max as u64).min(100) as u8
+    }
+
+    /// Get the canonical identity a contributor name resolves to
+    pub fn get_canonical_contributor(&self, contributor: String) -> String {
+        self.canonical_contributor(&contributor)
+    }
+
+    /// Get the aliases linked to a canonical contributor
+    pub fn get_contributor_aliases(&self, canonical: String) -> Vec<String> {
+        self.contributor_aliases.get(&canonical).unwrap_or_default()
+    }
+
+    // ==================== INTERNAL METHODS ====================
+
+    fn internal_record_atom(
+        &mut self,
+        mut atom: ATOMOnChain,
+        expected_sequence: Option<u64>,
+    ) -> String {
+        // Validate
+        atom.repo = normalize_repo(&atom.repo);
+        assert!(atom.coherence_score <= self.max_coherence, "Invalid coherence score");
+        assert!(
+            self.atoms.get(&atom.atom_tag).is_none(),
+            "ATOM already recorded; use amend_atom to correct it"
+        );
+        assert_atom_field_lengths(&atom);
+        self.assert_valid_commit_hash(&atom.commit_hash);
+        self.assert_meets_record_minimum(&atom);
+        self.assert_capacity_for(1);
+        if let Some(expected) = expected_sequence {
+            assert_eq!(
+                expected, self.atom_sequence_counter,
+                "Sequence mismatch: concurrent write detected"
+            );
+        }
+
+        // Normalize duplicate entries so tallies count each once
+        atom.markers = dedup_preserving_order(atom.markers);
+        atom.phases_passed = dedup_preserving_order(atom.phases_passed);
+        self.assert_valid_phases(&atom.phases_passed);
+
+        // Verification is granted by governance, never self-declared
+        atom.verified = false;
+        atom.invalid = false;
+        atom.sequence = self.atom_sequence_counter;
+        atom.block_height = env::block_height();
+        atom.recorded_by = Some(env::predecessor_account_id());
+        self.atom_sequence_counter += 1;
+
+        let atom_tag = atom.atom_tag.clone();
+        let (coherence, threshold) = self.store_new_atom(&atom);
+        let snapped_in = coherence >= threshold;
+        if snapped_in {
+            env::log_str(&format!(
+                "SNAP-IN: {} achieved {}% coherence",
+                atom.atom_tag, coherence
+            ));
+            self.hold_snap_in_reward(&atom, coherence, threshold);
+        }
+
+        self.emit_event(
+            "atom_recorded",
+            near_sdk::serde_json::json!({
+                "atom_tag": atom_tag,
+                "repo": atom.repo,
+                "contributor": atom.contributor,
+                "coherence": coherence,
+                "snap_in": snapped_in,
+            }),
+        );
+
+        // Return transaction hash equivalent
+        format!("{}:{}", atom.block_height, atom_tag)
+    }
+
+    // Store a new ATOM and fold it into every index and aggregate; returns its
+    // coherence percentage and the threshold it was measured against
+    fn store_new_atom(&mut self, atom: &ATOMOnChain) -> (u8, u8) {
+        // Store ATOM
+        self.atoms.insert(&atom.atom_tag, atom);
+
+        // Update repo state
+        self.update_repo_state(atom);
+        self.add_to_repo_index(atom);
+        self.atom_feed.push(&atom.atom_tag);
+        self.add_to_marker_index(&atom.atom_tag, &atom.markers);
+        self.update_phase_stats(atom);
+
+        // Update contributor trail
+        self.add_to_contributor_trail(atom);
+        self.update_contributor_stats(atom);
+
+        // Update global vortex state
+        self.update_vortex_state(atom);
+
+        // Check for ecosystem snap-in
+        let coherence = self.coherence_percent(atom.coherence_score);
+        let threshold = self.effective_threshold(&atom.repo);
+        if coherence >= threshold {
+            self.vortex_state.snap_in_count += 1;
+        }
+        (coherence, threshold)
+    }
+
+    // Scores are self-reported, so the reward waits on verify_atom
+    fn hold_snap_in_reward(&mut self, atom: &ATOMOnChain, coherence: u8, threshold: u8) {
+        if self.reward_token.is_none() || self.reward_per_snap_in == 0 {
+            return;
+        }
+        if atom.contributor.parse::<AccountId>().is_err() {
+            env::log_str(&format!(
+                "REWARD SKIPPED: {} is not a NEAR account",
+                atom.contributor
+            ));
+            return;
+        }
+        let amount = self.snap_in_reward(coherence, threshold);
+        self.pending_rewards.insert(&atom.atom_tag, &amount);
+    }
+
+    // Re-measure an unpaid reward against the amended coherence; paid rewards stand
+    fn rehold_snap_in_reward(&mut self, atom: &ATOMOnChain) {
+        if self.released_rewards.contains_key(&atom.atom_tag) {
+            return;
+        }
+        self.pending_rewards.remove(&atom.atom_tag);
+        let coherence = self.coherence_percent(atom.coherence_score);
+        let threshold = self.effective_threshold(&atom.repo);
+        if coherence >= threshold {
+            self.hold_snap_in_reward(atom, coherence, threshold);
+        }
+    }
+
+    fn credit_reward(&mut self, receiver: &AccountId, amount: u128) {
+        let balance = self.claimable_rewards.get(receiver).unwrap_or(0);
+        self.claimable_rewards.insert(receiver, &(balance + amount));
+    }
+
+    // Base reward at the threshold, rising linearly to twice the base at 100%
+    fn snap_in_reward(&self, coherence: u8, threshold: u8) -> u128 {
+        let base = self.reward_per_snap_in;
+        let span = 100u128.saturating_sub(threshold as u128);
+        if span == 0 {
+            return base;
+        }
+        let excess = coherence.saturating_sub(threshold) as u128;
+        base + base.saturating_mul(excess) / span
+    }
+
+    fn apply_coherence_delta(
+        &mut self,
+        atom: &ATOMOnChain,
+        old_coherence: u64,
+        new_coherence: u64,
+    ) {
+        if old_coherence == new_coherence {
+            return;
+        }
+
+        if let Some(mut state) = self.repos.get(&atom.repo) {
+            state.total_coherence =
+                state.total_coherence.saturating_sub(old_coherence) + new_coherence;
+            state.average_coherence =
+                safe_average(state.total_coherence as u128, state.atom_count);
+            self.repos.insert(&atom.repo, &state);
+        }
+
+        let threshold = self.effective_threshold(&atom.repo) as u64;
+        let contributor = self.canonical_contributor(&atom.contributor);
+        if let Some(mut stats) = self.contributor_stats.get(&contributor) {
+            stats.coherence_sum = stats.coherence_sum.saturating_sub(old_coherence) + new_coherence;
+            match (old_coherence >= threshold, new_coherence >= threshold) {
+                (true, false) => stats.snap_in_count = stats.snap_in_count.saturating_sub(1),
+                (false, true) => stats.snap_in_count += 1,
+                _ => {}
+            }
+            self.contributor_stats.insert(&contributor, &stats);
+        }
+
+        let vortex = &mut self.vortex_state;
+        vortex.total_coherence_sum =
+            vortex.total_coherence_sum.saturating_sub(old_coherence) + new_coherence;
+        vortex.average_coherence =
+            safe_average(vortex.total_coherence_sum as u128, vortex.total_atoms);
+        vortex.last_update = env::block_timestamp().to_string();
+    }
+
+    // Highest registered weight among the ATOM's markers
+    fn atom_weight(&self, atom: &ATOMOnChain) -> u8 {
+        atom.markers
+            .iter()
+            .filter_map(|marker| self.marker_weights.get(marker))
+            .max()
+            .unwrap_or(DEFAULT_MARKER_WEIGHT)
+    }
+
+    // Rebase a repo's total to coherence * atom_count; false if the repo is unknown
+    fn rebase_repo_coherence(&mut self, repo: &String, coherence: u8) -> bool {
+        let Some(mut state) = self.repos.get(repo) else {
+            return false;
+        };
+        let rebased_total = coherence as u64 * state.atom_count;
+
+        // Keep the ecosystem accumulator in step with the repo total
+        self.vortex_state.total_coherence_sum =
+            self.vortex_state.total_coherence_sum - state.total_coherence + rebased_total;
+        self.vortex_state.average_coherence = safe_average(
+            self.vortex_state.total_coherence_sum as u128,
+            self.vortex_state.total_atoms,
+        );
+
+        state.average_coherence = coherence;
+        state.total_coherence = rebased_total;
+        self.repos.insert(repo, &state);
+        true
+    }
+
+    // Log a NEP-297 event under this deployment's standard and version
+    fn emit_event(&self, event: &str, data: near_sdk::serde_json::Value) {
+        let payload = near_sdk::serde_json::json!({
+            "standard": self.event_standard,
+            "version": self.event_version,
+            "event": event,
+            "data": [data],
+        });
+        env::log_str(&format!("EVENT_JSON:{}", payload));
+    }
+
+    fn assert_owner(&self) {
+        assert_eq!(
+            env::predecessor_account_id(),
+            self.owner,
+            "Only owner"
+        );
+    }
+
+    fn assert_admin_or_owner(&self) {
+        let caller = env::predecessor_account_id();
+        assert!(
+            caller == self.owner || self.admins.contains(&caller),
+            "Only owner or admin"
+        );
+    }
+
+    fn assert_valid_phases(&self, phases: &[String]) {
+        if self.phase_registry.is_empty() {
+            return;
+        }
+        for phase in phases {
+            assert!(self.phase_registry.contains(phase), "Unknown phase {}", phase);
+        }
+        if self.phase_order_enforced {
+            assert!(
+                self.phase_registry.starts_with(phases),
+                "Phases must follow the pipeline order {:?}",
+                self.phase_registry
+            );
+        }
+    }
+
+    fn assert_meets_record_minimum(&self, atom: &ATOMOnChain) {
+        let floor = self.effective_min_record_coherence(&atom.repo);
+        assert!(
+            self.coherence_percent(atom.coherence_score) >= floor,
+            "Coherence below the recording minimum of {} for {}",
+            floor,
+            atom.repo
+        );
+    }
+
+    fn assert_valid_commit_hash(&self, commit_hash: &str) {
+        if !self.validate_commit_hash {
+            return;
+        }
+        assert!(
+            commit_hash.len() >= MIN_COMMIT_HASH_LEN
+                && commit_hash.len() <= MAX_COMMIT_HASH_LEN
+                && commit_hash.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f')),
+            "Commit hash must be {}-{} lowercase hex characters",
+            MIN_COMMIT_HASH_LEN,
+            MAX_COMMIT_HASH_LEN
+        );
+    }
+
+    fn assert_capacity_for(&self, additional: u64) {
+        if let Some(max_total_atoms) = self.max_total_atoms {
+            assert!(
+                self.atom_sequence_counter + additional <= max_total_atoms,
+                "ecosystem capacity reached ({} of {} ATOMs)",
+                self.atom_sequence_counter,
+                max_total_atoms
+            );
+        }
+    }
+
+    fn effective_threshold(&self, repo: &String) -> u8 {
+        self.repo_thresholds.get(repo).unwrap_or(self.snap_in_threshold)
+    }
+
+    // Coherence of the valid ATOMs among a repo's newest `window` index entries
+    fn recent_valid_coherence(&self, repo: &String, window: u32) -> Vec<u8> {
+        let Some(index) = self.repo_atoms.get(repo) else {
+            return Vec::new();
+        };
+        (index.len().saturating_sub(window as u64)..index.len())
+            .filter_map(|i| index.get(i))
+            .filter_map(|tag| self.atoms.get(&tag))
+            .filter(|atom| !atom.invalid)
+            .map(|atom| self.coherence_percent(atom.coherence_score))
+            .collect()
+    }
+
+    fn effective_min_record_coherence(&self, repo: &String) -> u8 {
+        self.repo_min_record_coherence
+            .get(repo)
+            .unwrap_or(self.min_record_coherence)
+    }
+
+    fn is_expired(atom: &ATOMOnChain, now: u64) -> bool {
+        atom.expires_at_ns.is_some_and(|expires_at| expires_at <= now)
+    }
+
+    fn coherence_percent(&self, score: u8) -> u8 {
+        (score as u64 * 100 / self.max_coherence as u64) as u8
+    }
+
+    fn update_repo_state(&mut self, atom: &ATOMOnChain) {
+        let mut state = self.repos.get(&atom.repo).unwrap_or(RepoState {
+            repo: atom.repo.clone(),
+            atom_count: 0,
+            total_coherence: 0,
+            average_coherence: 0,
+            last_snap_in: None,
+            weighted_coherence_total: 0,
+            weight_total: 0,
+            weighted_average_coherence: 0,
+            display_name: None,
+            first_snap_in_atom: None,
+            coherence_at_last_snapshot: 0,
+            contributor_count: 0,
+        });
+
+        state.atom_count += 1;
+        let coherence = self.coherence_percent(atom.coherence_score);
+        state.total_coherence += coherence as u64;
+
+        let mut contributors = self.repo_contributors.get(&atom.repo).unwrap_or_else(|| {
+            UnorderedSet::new(StorageKey::RepoContributorSet {
+                repo_hash: env::sha256(atom.repo.as_bytes()),
+            })
+        });
+        if contributors.insert(&self.canonical_contributor(&atom.contributor)) {
+            state.contributor_count += 1;
+            self.repo_contributors.insert(&atom.repo, &contributors);
+        }
+        state.average_coherence =
+            safe_average(state.total_coherence as u128, state.atom_count);
+
+        let threshold = self.effective_threshold(&atom.repo);
+        if coherence >= threshold {
+            state.last_snap_in = Some(atom.timestamp.clone());
+        }
+        if state.first_snap_in_atom.is_none() && state.average_coherence >= threshold {
+            state.first_snap_in_atom = Some(atom.atom_tag.clone());
+        }
+
+        if self.weighted_averages_enabled {
+            let weight = self.atom_weight(atom) as u64;
+            state.weighted_coherence_total += coherence as u64 * weight;
+            state.weight_total += weight;
+            state.weighted_average_coherence =
+                safe_average(state.weighted_coherence_total as u128, state.weight_total);
+        }
+
+        let mut series = self.coherence_series.get(&atom.repo).unwrap_or_default();
+        if series.len() >= COHERENCE_SERIES_LEN {
+            series.remove(0);
+        }
+        series.push(state.average_coherence);
+        self.coherence_series.insert(&atom.repo, &series);
+
+        self.repos.insert(&atom.repo, &state);
+    }
+
+    fn add_to_repo_index(&mut self, atom: &ATOMOnChain) {
+        let mut index = self.repo_atoms.get(&atom.repo).unwrap_or_else(|| {
+            Vector::new(StorageKey::RepoAtomIndex {
+                repo_hash: env::sha256(atom.repo.as_bytes()),
+            })
+        });
+
+        index.push(&atom.atom_tag);
+        self.repo_atoms.insert(&atom.repo, &index);
+    }
+
+    fn add_to_marker_index(&mut self, atom_tag: &String, markers: &[String]) {
+        for marker in markers {
+            let mut index = self.marker_atoms.get(marker).unwrap_or_else(|| {
+                Vector::new(StorageKey::MarkerAtomIndex {
+                    marker_hash: env::sha256(marker.as_bytes()),
+                })
+            });
+
+            index.push(atom_tag);
+            self.marker_atoms.insert(marker, &index);
+        }
+    }
+
+    fn repo_atoms_page(&self, repo: &String, from_index: u64, limit: u32) -> Vec<ATOMOnChain> {
+        if let Some(index) = self.repo_atoms.get(repo) {
+            (from_index..index.len())
+                .take(limit as usize)
+                .filter_map(|i| index.get(i))
+                .filter_map(|tag| self.atoms.get(&tag))
+                .collect()
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn update_phase_stats(&mut self, atom: &ATOMOnChain) {
+        let mut repo_counts = self.repo_phase_counts.get(&atom.repo).unwrap_or_else(|| {
+            UnorderedMap::new(StorageKey::RepoPhaseCountMap {
+                repo_hash: env::sha256(atom.repo.as_bytes()),
+            })
+        });
+
+        for phase in &atom.phases_passed {
+            let count = self.phase_counts.get(phase).unwrap_or(0);
+            self.phase_counts.insert(phase, &(count + 1));
+
+            let repo_count = repo_counts.get(phase).unwrap_or(0);
+            repo_counts.insert(phase, &(repo_count + 1));
+        }
+
+        self.repo_phase_counts.insert(&atom.repo, &repo_counts);
+    }
+
+    // Move global and per-repo phase counts from previous's phases to updated's
+    fn apply_phase_delta(&mut self, previous: &ATOMOnChain, updated: &ATOMOnChain) {
+        let mut repo_counts = match self.repo_phase_counts.get(&previous.repo) {
+            Some(counts) => counts,
+            None => return,
+        };
+
+        for phase in &previous.phases_passed {
+            if updated.phases_passed.contains(phase) {
+                continue;
+            }
+            match self.phase_counts.get(phase).unwrap_or(0) {
+                0 | 1 => self.phase_counts.remove(phase),
+                count => self.phase_counts.insert(phase, &(count - 1)),
+            };
+            match repo_counts.get(phase).unwrap_or(0) {
+                0 | 1 => repo_counts.remove(phase),
+                count => repo_counts.insert(phase, &(count - 1)),
+            };
+        }
+        for phase in &updated.phases_passed {
+            if previous.phases_passed.contains(phase) {
+                continue;
+            }
+            let count = self.phase_counts.get(phase).unwrap_or(0);
+            self.phase_counts.insert(phase, &(count + 1));
+            let repo_count = repo_counts.get(phase).unwrap_or(0);
+            repo_counts.insert(phase, &(repo_count + 1));
+        }
+
+        self.repo_phase_counts.insert(&previous.repo, &repo_counts);
+    }
+
+    fn add_to_contributor_trail(&mut self, atom: &ATOMOnChain) {
+        let contributor = self.canonical_contributor(&atom.contributor);
+        let mut trail = match self.contributor_atoms.get(&contributor) {
+            Some(trail) => trail,
+            None => {
+                // A linked alias's trail already counted this contributor
+                if !self.has_linked_trail(&contributor) {
+                    self.vortex_state.contributor_count += 1;
+                }
+                Vector::new(StorageKey::ContributorTrail {
+                    contributor_hash: env::sha256(contributor.as_bytes()),
+                })
+            }
+        };
+
+        trail.push(&atom.atom_tag);
+        self.contributor_atoms.insert(&contributor, &trail);
+    }
+
+    fn canonical_contributor(&self, contributor: &String) -> String {
+        self.contributor_links
+            .get(contributor)
+            .unwrap_or_else(|| contributor.clone())
+    }
+
+    // Whether the canonical identity or any of its aliases has a trail
+    fn has_linked_trail(&self, canonical: &String) -> bool {
+        self.linked_identities(canonical)
+            .iter()
+            .any(|identity| self.contributor_atoms.contains_key(identity))
+    }
+
+    // The canonical identity followed by each of its linked aliases
+    fn linked_identities(&self, contributor: &String) -> Vec<String> {
+        let canonical = self.canonical_contributor(contributor);
+        let mut identities = vec![canonical.clone()];
+        identities.extend(self.contributor_aliases.get(&canonical).unwrap_or_default());
+        identities
+    }
+
+    // Total length of the canonical and alias trails, without reading their tags
+    fn linked_trail_len(&self, contributor: &String) -> u64 {
+        self.linked_identities(contributor)
+            .iter()
+            .filter_map(|identity| self.contributor_atoms.get(identity))
+            .map(|trail| trail.len())
+            .sum()
+    }
+
+    // Tags from the canonical trail followed by each linked alias's trail
+    fn linked_trail_tags(&self, contributor: &String) -> Vec<String> {
+        self.linked_identities(contributor)
+            .iter()
+            .filter_map(|identity| self.contributor_atoms.get(identity))
+            .flat_map(|trail| trail.iter().collect::<Vec<String>>())
+            .collect()
+    }
+
+    fn linked_trail_atoms(&self, contributor: &String) -> Vec<ATOMOnChain> {
+        self.linked_trail_tags(contributor)
+            .iter()
+            .filter_map(|tag| self.atoms.get(tag))
+            .collect()
+    }
+
+    fn update_contributor_stats(&mut self, atom: &ATOMOnChain) {
+        let contributor = self.canonical_contributor(&atom.contributor);
+        let mut stats = self.contributor_stats.get(&contributor).unwrap_or(ContributorStats {
+            atom_count: 0,
+            coherence_sum: 0,
+            snap_in_count: 0,
+            first_seen: None,
+            last_seen: None,
+        });
+
+        self.apply_atom_to_stats(&mut stats, atom);
+        self.contributor_stats.insert(&contributor, &stats);
+    }
+
+    fn apply_atom_to_stats(&self, stats: &mut ContributorStats, atom: &ATOMOnChain) {
+        let coherence = self.coherence_percent(atom.coherence_score);
+
+        stats.atom_count += 1;
+        stats.coherence_sum += coherence as u64;
+        if coherence >= self.effective_threshold(&atom.repo) {
+            stats.snap_in_count += 1;
+        }
+        if stats.first_seen.is_none() {
+            stats.first_seen = Some(atom.timestamp.clone());
+        }
+        stats.last_seen = Some(atom.timestamp.clone());
+    }
+
+    fn update_vortex_state(&mut self, atom: &ATOMOnChain) {
+        self.vortex_state.total_atoms += 1;
+        let coherence = self.coherence_percent(atom.coherence_score);
+        self.vortex_state.total_coherence_sum += coherence as u64;
+
+        self.vortex_state.average_coherence = safe_average(
+            self.vortex_state.total_coherence_sum as u128,
+            self.vortex_state.total_atoms,
+        );
         self.vortex_state.last_update = env::block_timestamp().to_string();
+
+        if self.vortex_history.len() >= VORTEX_HISTORY_LEN {
+            self.vortex_history.remove(0);
+        }
+        self.vortex_history.push(self.vortex_state.average_coherence);
+    }
+}
+
+// Integer mean of `count` values summing to `sum`; 0 when there are none
+fn safe_average(sum: u128, count: u64) -> u8 {
+    if count == 0 {
+        0
+    } else {
+        (sum / count as u128) as u8
+    }
+}
+
+// Middle value, or the mean of the two middle values; 0 when empty
+fn median(mut values: Vec<u8>) -> u8 {
+    if values.is_empty() {
+        return 0;
+    }
+    values.sort_unstable();
+    let mid = values.len() / 2;
+    if values.len() % 2 == 1 {
+        values[mid]
+    } else {
+        safe_average(values[mid - 1] as u128 + values[mid] as u128, 2)
+    }
+}
+
+// Drop repeated entries, keeping the first occurrence of each
+fn dedup_preserving_order(values: Vec<String>) -> Vec<String> {
+    let mut seen = HashSet::new();
+    values
+        .into_iter()
+        .filter(|value| seen.insert(value.clone()))
+        .collect()
+}
+
+// Canonical repo key: surrounding whitespace trimmed, lowercased
+fn normalize_repo(repo: &str) -> String {
+    repo.trim().to_lowercase()
+}
+
+// Reject empty keys and strings long enough to bloat the indexes
+fn assert_atom_field_lengths(atom: &ATOMOnChain) {
+    assert!(!atom.atom_tag.is_empty(), "ATOM tag required");
+    assert!(!atom.repo.is_empty(), "Repo required");
+    assert!(!atom.contributor.is_empty(), "Contributor required");
+    assert!(
+        atom.atom_tag.len() <= MAX_ATOM_TAG_LEN,
+        "ATOM tag exceeds {} bytes",
+        MAX_ATOM_TAG_LEN
+    );
+    assert!(atom.repo.len() <= MAX_REPO_LEN, "Repo exceeds {} bytes", MAX_REPO_LEN);
+    assert!(
+        atom.contributor.len() <= MAX_CONTRIBUTOR_LEN,
+        "Contributor exceeds {} bytes",
+        MAX_CONTRIBUTOR_LEN
+    );
+    assert!(
+        atom.commit_hash.len() <= MAX_COMMIT_HASH_LEN,
+        "Commit hash exceeds {} bytes",
+        MAX_COMMIT_HASH_LEN
+    );
+}
+
+#[cfg(test)]
+impl SpiralSafeVortex {
+    fn set_contributor_coherence_sum(&mut self, contributor: &String, coherence_sum: u64) {
+        let mut stats = self.contributor_stats.get(contributor).expect("Unknown contributor");
+        stats.coherence_sum = coherence_sum;
+        self.contributor_stats.insert(contributor, &stats);
+    }
+
+    // Drop a contributor's stats entry, as in state from before stats tracking
+    fn forget_contributor_stats(&mut self, contributor: &String) {
+        self.contributor_stats.remove(contributor);
+    }
+
+    // Overwrite a repo's totals, carrying the error into the ecosystem sums
+    // the way a double-applied write would
+    fn corrupt_repo_totals(&mut self, repo: &String, total_coherence: u64, atom_count: u64) {
+        let mut state = self.repos.get(repo).expect("Repo not found");
+        self.vortex_state.total_coherence_sum =
+            self.vortex_state.total_coherence_sum - state.total_coherence + total_coherence;
+        self.vortex_state.total_atoms =
+            self.vortex_state.total_atoms - state.atom_count + atom_count;
+        state.total_coherence = total_coherence;
+        state.atom_count = atom_count;
+        state.average_coherence = safe_average(total_coherence as u128, atom_count);
+        self.repos.insert(repo, &state);
+    }
+}
+
+// ==================== TESTS ====================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ed25519 keypair from a fixed seed of [7; 32], signing "ATOM-SIGNED-1" ++ [80]
+    const TEST_PUBLIC_KEY: [u8; 32] = [
+        234, 74, 108, 99, 226, 156, 82, 10, 190, 245, 80, 123, 19, 46, 197, 249, 149, 71, 118,
+        174, 190, 190, 123, 146, 66, 30, 234, 105, 20, 70, 210, 44,
+    ];
+    const TEST_SIGNATURE: [u8; 64] = [
+        113, 62, 102, 94, 117, 97, 122, 177, 162, 205, 157, 118, 115, 247, 213, 199, 98, 98, 4,
+        120, 184, 202, 181, 19, 191, 226, 162, 242, 210, 13, 77, 180, 120, 104, 229, 2, 211, 211,
+        52, 141, 233, 191, 166, 228, 1, 4, 148, 123, 46, 153, 171, 202, 176, 139, 95, 56, 233, 78,
+        46, 249, 223, 123, 226, 9,
+    ];
+
+    fn get_context() -> near_sdk::VMContext {
+        near_sdk::test_utils::VMContextBuilder::new()
+            .predecessor_account_id("owner.near".parse().unwrap())
+            .build()
+    }
+
+    #[test]
+    fn test_record_atom() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+
+        let atom = ATOMOnChain {
+            atom_tag: "ATOM-TEST-001".to_string(),
+            repo: "QDI".to_string(),
+            coherence_score: 75,
+            phases_passed: vec!["KENL".to_string(), "AWI".to_string()],
+            markers: vec!["WAVE".to_string(), "PASS".to_string()],
+            contributor: "toolate28".to_string(),
+            timestamp: "2026-01-17T00:00:00Z".to_string(),
+            commit_hash: "abc1234".to_string(),
+            pr_number: Some(42),
+            expires_at_ns: None,
+            verified: false,
+            sequence: 0,
+            block_height: 0,
+            recorded_by: None,
+            invalid: false,
+        };
+
+        let result = contract.record_atom(atom.clone());
+        assert!(!result.is_empty());
+
+        let retrieved = contract.get_atom("ATOM-TEST-001".to_string());
+        assert!(retrieved.is_some());
+        assert_eq!(retrieved.unwrap().coherence_score, 75);
+    }
+
+    #[test]
+    fn test_snap_in_detection() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+
+        // Record atoms until snap-in
+        for i in 0..5 {
+            let atom = ATOMOnChain {
+                atom_tag: format!("ATOM-TEST-{}", i),
+                repo: "QDI".to_string(),
+                coherence_score: 80,  // Above threshold
+                phases_passed: vec![],
+                markers: vec!["WAVE".to_string()],
+                contributor: "test".to_string(),
+                timestamp: "2026-01-17".to_string(),
+                commit_hash: "abc1234".to_string(),
+                pr_number: None,
+                expires_at_ns: None,
+                verified: false,
+                sequence: 0,
+                block_height: 0,
+                recorded_by: None,
+                invalid: false,
+            };
+            contract.record_atom(atom);
+        }
+
+        let (snap_in, coherence) = contract.check_ecosystem_snap_in();
+        assert!(snap_in);
+        assert_eq!(coherence, 80);
+    }
+
+    fn set_caller(account: &str) {
+        near_sdk::testing_env!(near_sdk::test_utils::VMContextBuilder::new()
+            .predecessor_account_id(account.parse().unwrap())
+            .build());
+    }
+
+    fn set_block_height(height: u64) {
+        near_sdk::testing_env!(near_sdk::test_utils::VMContextBuilder::new()
+            .predecessor_account_id("owner.near".parse().unwrap())
+            .block_height(height)
+            .build());
+    }
+
+    fn make_atom(
+        tag: &str,
+        repo: &str,
+        contributor: &str,
+        coherence: u8,
+        markers: &[&str],
+    ) -> ATOMOnChain {
+        ATOMOnChain {
+            atom_tag: tag.to_string(),
+            repo: repo.to_string(),
+            coherence_score: coherence,
+            phases_passed: vec![],
+            markers: markers.iter().map(|m| m.to_string()).collect(),
+            contributor: contributor.to_string(),
+            timestamp: "2026-01-17T00:00:00Z".to_string(),
+            commit_hash: "abc1234".to_string(),
+            pr_number: None,
+            expires_at_ns: None,
+            verified: false,
+            sequence: 0,
+            block_height: 0,
+            recorded_by: None,
+            invalid: false,
+        }
+    }
+
+    #[test]
+    fn test_ecosystem_health() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        assert_eq!(contract.get_ecosystem_health(), 0);
+
+        // avg 40, no snap-ins, 1 of 10 contributors: (50*40 + 30*0 + 20*10) / 100
+        contract.record_atom(make_atom("ATOM-HEALTH-1", "QDI", "alice", 40, &["WAVE"]));
+        assert_eq!(contract.get_ecosystem_health(), 22);
+
+        // avg 70, half snapped in, 1 of 10 contributors: (50*70 + 30*50 + 20*10) / 100
+        contract.record_atom(make_atom("ATOM-HEALTH-2", "QDI", "alice", 100, &["WAVE"]));
+        assert_eq!(contract.get_ecosystem_health(), 52);
+    }
+
+    #[test]
+    fn test_attribution_by_marker() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.record_atom(make_atom("ATOM-MARK-1", "QDI", "alice", 80, &["WAVE", "PASS"]));
+        contract.record_atom(make_atom("ATOM-MARK-2", "QDI", "alice", 60, &["WAVE"]));
+        contract.record_atom(make_atom("ATOM-MARK-3", "QDI", "alice", 20, &["PASS"]));
+
+        assert_eq!(
+            contract.get_attribution_by_marker("alice".to_string(), "WAVE".to_string()),
+            (2, 70)
+        );
+        assert_eq!(
+            contract.get_attribution_by_marker("alice".to_string(), "FAIL".to_string()),
+            (0, 0)
+        );
+    }
+
+    #[test]
+    fn test_unverified_atoms() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.record_atom(make_atom("ATOM-REVIEW-1", "QDI", "alice", 80, &["WAVE"]));
+        contract.record_atom(make_atom("ATOM-REVIEW-2", "QDI", "alice", 60, &["WAVE"]));
+
+        contract.verify_atom("ATOM-REVIEW-1".to_string());
+
+        let queue = contract.get_unverified_atoms("QDI".to_string(), 0, 10);
+        assert_eq!(queue.len(), 1);
+        assert_eq!(queue[0].atom_tag, "ATOM-REVIEW-2");
+    }
+
+    #[test]
+    fn test_update_coherence_persists() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.record_atom(make_atom("ATOM-OVR-1", "QDI", "alice", 40, &[]));
+
+        contract.update_coherence("QDI".to_string(), 80);
+        assert_eq!(contract.get_repo_state("QDI".to_string()).unwrap().average_coherence, 80);
+
+        // Override is folded into the running total: (80 + 60) / 2
+        contract.record_atom(make_atom("ATOM-OVR-2", "QDI", "alice", 60, &[]));
+        let state = contract.get_repo_state("QDI".to_string()).unwrap();
+        assert_eq!(state.total_coherence, 140);
+        assert_eq!(state.average_coherence, 70);
+    }
+
+    #[test]
+    fn test_repo_weights() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.record_atom(make_atom("ATOM-W-1", "QDI", "alice", 10, &[]));
+        contract.record_atom(make_atom("ATOM-W-2", "KENL", "alice", 10, &[]));
+        contract.record_atom(make_atom("ATOM-W-3", "KENL", "alice", 9, &[]));
+
+        let weights = contract.get_repo_weights();
+        assert_eq!(weights.len(), 2);
+
+        let sum: u32 = weights.iter().map(|(_, w)| *w as u32).sum();
+        assert!((98..=100).contains(&sum), "weights sum to {}", sum);
+    }
+
+    #[test]
+    fn test_phase_stats() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+
+        let mut first = make_atom("ATOM-PHASE-1", "QDI", "alice", 80, &[]);
+        first.phases_passed = vec!["KENL".to_string(), "AWI".to_string()];
+        contract.record_atom(first);
+
+        let mut second = make_atom("ATOM-PHASE-2", "QDI", "alice", 80, &[]);
+        second.phases_passed = vec!["KENL".to_string(), "AWI".to_string(), "ATOM".to_string()];
+        contract.record_atom(second);
+
+        let mut third = make_atom("ATOM-PHASE-3", "KENL", "alice", 80, &[]);
+        third.phases_passed = vec!["KENL".to_string()];
+        contract.record_atom(third);
+
+        let mut global = contract.get_phase_stats();
+        global.sort();
+        assert_eq!(
+            global,
+            vec![
+                ("ATOM".to_string(), 1),
+                ("AWI".to_string(), 2),
+                ("KENL".to_string(), 3),
+            ]
+        );
+
+        let mut qdi = contract.get_repo_phase_stats("QDI".to_string());
+        qdi.sort();
+        assert_eq!(
+            qdi,
+            vec![
+                ("ATOM".to_string(), 1),
+                ("AWI".to_string(), 2),
+                ("KENL".to_string(), 2),
+            ]
+        );
+        assert!(contract.get_repo_phase_stats("missing".to_string()).is_empty());
+    }
+
+    #[test]
+    fn test_record_atom_sequenced() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.record_atom(make_atom("ATOM-SEQ-0", "QDI", "alice", 80, &[]));
+        assert_eq!(contract.get_atom_sequence(), 1);
+
+        contract.record_atom_sequenced(make_atom("ATOM-SEQ-1", "QDI", "alice", 80, &[]), Some(1));
+        assert_eq!(contract.get_atom("ATOM-SEQ-1".to_string()).unwrap().sequence, 1);
+        assert_eq!(contract.get_atom_sequence(), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "Sequence mismatch")]
+    fn test_record_atom_sequenced_mismatch() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.record_atom(make_atom("ATOM-SEQ-0", "QDI", "alice", 80, &[]));
+
+        // A stale client still expects sequence 0
+        contract.record_atom_sequenced(make_atom("ATOM-SEQ-1", "QDI", "alice", 80, &[]), Some(0));
+    }
+
+    #[test]
+    fn test_max_coherence_scale() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.set_max_coherence(200);
+
+        // 150/200 = 75% snaps in, 120/200 = 60% does not
+        contract.record_atom(make_atom("ATOM-SCALE-1", "QDI", "alice", 150, &[]));
+        contract.record_atom(make_atom("ATOM-SCALE-2", "QDI", "alice", 120, &[]));
+
+        let state = contract.get_vortex_state();
+        assert_eq!(state.snap_in_count, 1);
+        assert_eq!(state.average_coherence, 67);
+        assert_eq!(contract.get_repo_state("QDI".to_string()).unwrap().average_coherence, 67);
+        assert_eq!(contract.get_atom("ATOM-SCALE-1".to_string()).unwrap().coherence_score, 150);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid coherence score")]
+    fn test_max_coherence_rejects_above_scale() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.set_max_coherence(200);
+        contract.record_atom(make_atom("ATOM-SCALE-3", "QDI", "alice", 201, &[]));
+    }
+
+    #[test]
+    #[should_panic(expected = "Coherence scale cannot change once ATOMs are recorded")]
+    fn test_max_coherence_locked_after_first_atom() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.record_atom(make_atom("ATOM-SCALE-4", "QDI", "alice", 90, &[]));
+        contract.set_max_coherence(50);
+    }
+
+    #[test]
+    fn test_contributor_marker_diversity() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.record_atom(make_atom("ATOM-DIV-1", "QDI", "alice", 80, &["WAVE", "PASS"]));
+        contract.record_atom(make_atom("ATOM-DIV-2", "QDI", "alice", 80, &["SAUCED"]));
+        contract.record_atom(make_atom("ATOM-DIV-3", "QDI", "bob", 80, &["WAVE"]));
+        contract.record_atom(make_atom("ATOM-DIV-4", "QDI", "bob", 80, &["WAVE"]));
+
+        let alice = contract.get_contributor_marker_diversity("alice".to_string());
+        let bob = contract.get_contributor_marker_diversity("bob".to_string());
+        assert_eq!(alice, 30);
+        assert_eq!(bob, 10);
+        assert!(alice > bob);
+        assert_eq!(contract.get_contributor_marker_diversity("nobody".to_string()), 0);
+    }
+
+    #[test]
+    fn test_get_atoms_batch() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.record_atom(make_atom("ATOM-BATCH-1", "QDI", "alice", 80, &[]));
+        contract.record_atom(make_atom("ATOM-BATCH-2", "QDI", "alice", 60, &[]));
+
+        let atoms = contract.get_atoms_batch(vec![
+            "ATOM-BATCH-2".to_string(),
+            "ATOM-MISSING".to_string(),
+            "ATOM-BATCH-1".to_string(),
+        ]);
+        assert_eq!(atoms.len(), 3);
+        assert_eq!(atoms[0].as_ref().unwrap().atom_tag, "ATOM-BATCH-2");
+        assert!(atoms[1].is_none());
+        assert_eq!(atoms[2].as_ref().unwrap().atom_tag, "ATOM-BATCH-1");
+    }
+
+    #[test]
+    #[should_panic(expected = "Too many tags")]
+    fn test_get_atoms_batch_cap() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        let tags = (0..=MAX_VIEW_BATCH_SIZE).map(|i| format!("ATOM-{}", i)).collect();
+        contract.get_atoms_batch(tags);
+    }
+
+    #[test]
+    fn test_recompute_contributor() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.record_atom(make_atom("ATOM-STATS-1", "QDI", "alice", 80, &[]));
+        contract.record_atom(make_atom("ATOM-STATS-2", "QDI", "alice", 40, &[]));
+
+        let alice = "alice".to_string();
+        assert!(!contract.recompute_contributor(alice.clone(), 10));
+
+        contract.set_contributor_coherence_sum(&alice, 999);
+        assert!(contract.recompute_contributor(alice.clone(), 10));
+
+        let stats = contract.get_contributor_stats(alice).unwrap();
+        assert_eq!(stats.atom_count, 2);
+        assert_eq!(stats.coherence_sum, 120);
+        assert_eq!(stats.snap_in_count, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds max_atoms")]
+    fn test_recompute_contributor_bounded() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.record_atom(make_atom("ATOM-STATS-1", "QDI", "alice", 80, &[]));
+        contract.record_atom(make_atom("ATOM-STATS-2", "QDI", "alice", 40, &[]));
+
+        contract.recompute_contributor("alice".to_string(), 1);
+    }
+
+    #[test]
+    fn test_flag_and_resolve() {
+        set_caller("uploader.near");
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.record_atom(make_atom("ATOM-FLAG-1", "QDI", "alice", 80, &[]));
+        assert!(contract.get_flag("ATOM-FLAG-1".to_string()).is_none());
+
+        contract.flag_atom("ATOM-FLAG-1".to_string(), "wrong contributor".to_string());
+        let flag = contract.get_flag("ATOM-FLAG-1".to_string()).unwrap();
+        assert_eq!(flag.flagged_by.as_str(), "uploader.near");
+        assert_eq!(flag.reason, "wrong contributor");
+        assert!(!flag.resolved);
+
+        // Flagged ATOMs still count toward averages
+        assert_eq!(contract.get_vortex_state().average_coherence, 80);
+
+        set_caller("owner.near");
+        contract.resolve_flag("ATOM-FLAG-1".to_string());
+        assert!(contract.get_flag("ATOM-FLAG-1".to_string()).unwrap().resolved);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the uploader or owner can flag an ATOM")]
+    fn test_flag_rejects_stranger() {
+        set_caller("uploader.near");
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.record_atom(make_atom("ATOM-FLAG-1", "QDI", "alice", 80, &[]));
+
+        set_caller("stranger.near");
+        contract.flag_atom("ATOM-FLAG-1".to_string(), "spite".to_string());
+    }
+
+    #[test]
+    fn test_repo_atom_gini_even() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut even = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        for (i, repo) in ["QDI", "KENL", "AWI"].iter().enumerate() {
+            even.record_atom(make_atom(&format!("ATOM-EVEN-{}-a", i), repo, "alice", 80, &[]));
+            even.record_atom(make_atom(&format!("ATOM-EVEN-{}-b", i), repo, "alice", 80, &[]));
+        }
+        assert_eq!(even.get_repo_atom_gini(10), 0);
+    }
+
+    #[test]
+    fn test_repo_atom_gini_skewed() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut skewed = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        skewed.record_atom(make_atom("ATOM-SKEW-KENL", "KENL", "alice", 80, &[]));
+        skewed.record_atom(make_atom("ATOM-SKEW-AWI", "AWI", "alice", 80, &[]));
+        for i in 0..10 {
+            skewed.record_atom(make_atom(&format!("ATOM-SKEW-{}", i), "QDI", "alice", 80, &[]));
+        }
+        assert_eq!(skewed.get_repo_atom_gini(10), 75);
+    }
+
+    #[test]
+    fn test_invalidate_atom() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.record_atom(make_atom("ATOM-INV-1", "QDI", "alice", 80, &[]));
+        contract.record_atom(make_atom("ATOM-INV-2", "QDI", "alice", 40, &[]));
+        assert_eq!(contract.get_vortex_state().average_coherence, 60);
+
+        contract.flag_atom("ATOM-INV-2".to_string(), "fraudulent run".to_string());
+        contract.invalidate_atom("ATOM-INV-2".to_string());
+
+        let repo = contract.get_repo_state("QDI".to_string()).unwrap();
+        assert_eq!(repo.atom_count, 1);
+        assert_eq!(repo.average_coherence, 80);
+        let vortex = contract.get_vortex_state();
+        assert_eq!(vortex.total_atoms, 1);
+        assert_eq!(vortex.average_coherence, 80);
+        let stats = contract.get_contributor_stats("alice".to_string()).unwrap();
+        assert_eq!(stats.atom_count, 1);
+        assert_eq!(stats.coherence_sum, 80);
+        assert_eq!(stats.snap_in_count, 1);
+
+        // Still stored for audit, optionally hidden from repo views
+        assert!(contract.get_atom("ATOM-INV-2".to_string()).unwrap().invalid);
+        assert_eq!(contract.get_repo_atoms("QDI".to_string(), 10, None).len(), 2);
+        assert_eq!(contract.get_repo_atoms("QDI".to_string(), 10, Some(true)).len(), 1);
+    }
+
+    #[test]
+    fn test_invalidate_snap_in_atom() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.record_atom(make_atom("ATOM-INV-1", "QDI", "alice", 80, &[]));
+        contract.record_atom(make_atom("ATOM-INV-2", "QDI", "alice", 90, &[]));
+
+        for tag in ["ATOM-INV-1", "ATOM-INV-2"] {
+            contract.flag_atom(tag.to_string(), "fraudulent run".to_string());
+            contract.invalidate_atom(tag.to_string());
+            let vortex = contract.get_vortex_state();
+            assert!(vortex.snap_in_count <= vortex.total_atoms);
+        }
+        let vortex = contract.get_vortex_state();
+        assert_eq!(vortex.total_atoms, 0);
+        assert_eq!(vortex.snap_in_count, 0);
+
+        // Rebuilding the stats leaves the invalidated ATOMs out
+        contract.recompute_contributor("alice".to_string(), 10);
+        let stats = contract.get_contributor_stats("alice".to_string()).unwrap();
+        assert_eq!(stats.atom_count, 0);
+        assert_eq!(stats.snap_in_count, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "ATOM already invalidated")]
+    fn test_invalidate_atom_once() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.record_atom(make_atom("ATOM-INV-1", "QDI", "alice", 80, &[]));
+        contract.flag_atom("ATOM-INV-1".to_string(), "fraudulent run".to_string());
+
+        contract.invalidate_atom("ATOM-INV-1".to_string());
+        contract.invalidate_atom("ATOM-INV-1".to_string());
+    }
+
+    #[test]
+    fn test_repo_valid_coherence_excludes_expired() {
+        near_sdk::testing_env!(near_sdk::test_utils::VMContextBuilder::new()
+            .predecessor_account_id("owner.near".parse().unwrap())
+            .block_timestamp(1_000)
+            .build());
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+
+        let mut expired = make_atom("ATOM-CERT-OLD", "QDI", "alice", 40, &[]);
+        expired.expires_at_ns = Some(500);
+        contract.record_atom(expired);
+
+        let mut valid = make_atom("ATOM-CERT-NEW", "QDI", "alice", 90, &[]);
+        valid.expires_at_ns = Some(5_000);
+        contract.record_atom(valid);
+
+        assert!(contract.is_atom_expired("ATOM-CERT-OLD".to_string()));
+        assert!(!contract.is_atom_expired("ATOM-CERT-NEW".to_string()));
+        assert_eq!(contract.get_repo_valid_coherence("QDI".to_string(), 10), 90);
+
+        // Expired data is retained and still part of the raw average
+        assert!(contract.get_atom("ATOM-CERT-OLD".to_string()).is_some());
+        assert_eq!(contract.get_repo_state("QDI".to_string()).unwrap().average_coherence, 65);
+    }
+
+    #[test]
+    fn test_atoms_in_height_range() {
+        set_block_height(100);
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+
+        for (i, height) in [100u64, 110, 120, 130].iter().enumerate() {
+            set_block_height(*height);
+            contract.record_atom(make_atom(&format!("ATOM-H-{}", i), "QDI", "alice", 80, &[]));
+        }
+        assert_eq!(contract.get_atom("ATOM-H-2".to_string()).unwrap().block_height, 120);
+
+        let range = contract.get_atoms_in_height_range("QDI".to_string(), 105, 125, 10);
+        let tags: Vec<String> = range.into_iter().map(|a| a.atom_tag).collect();
+        assert_eq!(tags, vec!["ATOM-H-1".to_string(), "ATOM-H-2".to_string()]);
+
+        let limited = contract.get_atoms_in_height_range("QDI".to_string(), 0, 200, 3);
+        assert_eq!(limited.len(), 3);
+    }
+
+    #[test]
+    fn test_project_ecosystem_coherence() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.record_atom(make_atom("ATOM-TREND-1", "QDI", "alice", 40, &[]));
+        contract.record_atom(make_atom("ATOM-TREND-2", "QDI", "alice", 60, &[]));
+        contract.record_atom(make_atom("ATOM-TREND-3", "QDI", "alice", 80, &[]));
+
+        // Averages 40 -> 50 -> 60, so +10 per ATOM
+        assert_eq!(contract.get_vortex_history(), vec![40, 50, 60]);
+        let projected = contract.project_ecosystem_coherence(2);
+        assert!(projected > contract.get_vortex_state().average_coherence);
+        assert_eq!(projected, 80);
+        assert_eq!(contract.project_ecosystem_coherence(100), 100);
+    }
+
+    #[test]
+    fn test_snap_in_reward_transfer() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.set_reward_token("token.near".parse().unwrap());
+        contract.set_reward_per_snap_in(U128(1_000));
+
+        // 85% is halfway from the 70% threshold to 100%: 1.5x the base reward
+        contract.record_atom(make_atom("ATOM-REWARD-1", "QDI", "alice.near", 85, &[]));
+        assert_eq!(contract.get_pending_reward("ATOM-REWARD-1".to_string()), Some(U128(1_500)));
+        let alice: AccountId = "alice.near".parse().unwrap();
+        assert_eq!(contract.get_claimable_rewards(alice.clone()), U128(0));
+
+        contract.verify_atom("ATOM-REWARD-1".to_string());
+        assert_eq!(contract.get_pending_reward("ATOM-REWARD-1".to_string()), None);
+        assert_eq!(contract.get_claimable_rewards(alice.clone()), U128(1_500));
+
+        set_caller("alice.near");
+        let _ = contract.claim_rewards();
+        assert_eq!(contract.get_claimable_rewards(alice), U128(0));
+
+        let receipts = near_sdk::test_utils::get_created_receipts();
+        let transfer = receipts
+            .iter()
+            .find(|r| r.receiver_id.as_str() == "token.near")
+            .expect("No receipt to the reward token");
+        match &transfer.actions[0] {
+            near_sdk::mock::MockAction::FunctionCallWeight {
+                method_name,
+                args,
+                attached_deposit,
+                ..
+            } => {
+                assert_eq!(method_name.as_slice(), b"ft_transfer");
+                assert_eq!(*attached_deposit, NearToken::from_yoctonear(1));
+                let args: near_sdk::serde_json::Value =
+                    near_sdk::serde_json::from_slice(args).unwrap();
+                assert_eq!(args["receiver_id"], "alice.near");
+                assert_eq!(args["amount"], "1500");
+            }
+            _ => panic!("Expected an ft_transfer function call"),
+        }
+    }
+
+    #[test]
+    fn test_snap_in_reward_dropped_and_recredited() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.set_reward_token("token.near".parse().unwrap());
+        contract.set_reward_per_snap_in(U128(1_000));
+
+        // Invalidated before verification: the held reward is never paid
+        contract.record_atom(make_atom("ATOM-REWARD-2", "QDI", "alice.near", 70, &[]));
+        contract.flag_atom("ATOM-REWARD-2".to_string(), "fraudulent run".to_string());
+        contract.invalidate_atom("ATOM-REWARD-2".to_string());
+        assert_eq!(contract.get_pending_reward("ATOM-REWARD-2".to_string()), None);
+        contract.verify_atom("ATOM-REWARD-2".to_string());
+        let alice: AccountId = "alice.near".parse().unwrap();
+        assert_eq!(contract.get_claimable_rewards(alice.clone()), U128(0));
+
+        // A failed claim transfer is credited back for a retry
+        contract.on_reward_claim(alice.clone(), U128(1_000), Err(PromiseError::Failed));
+        assert_eq!(contract.get_claimable_rewards(alice.clone()), U128(1_000));
+        contract.on_reward_claim(alice.clone(), U128(1_000), Ok(()));
+        assert_eq!(contract.get_claimable_rewards(alice), U128(1_000));
+    }
+
+    #[test]
+    fn test_amended_snap_in_reward_follows_coherence() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.set_reward_token("token.near".parse().unwrap());
+        contract.set_reward_per_snap_in(U128(1_000));
+        let alice: AccountId = "alice.near".parse().unwrap();
+
+        // Amended below the threshold before verification: nothing is paid
+        contract.record_atom(make_atom("ATOM-REWARD-3", "QDI", "alice.near", 100, &[]));
+        assert_eq!(contract.get_pending_reward("ATOM-REWARD-3".to_string()), Some(U128(2_000)));
+        contract.amend_atom(
+            "ATOM-REWARD-3".to_string(),
+            make_atom("ATOM-REWARD-3", "QDI", "alice.near", 10, &[]),
+        );
+        assert_eq!(contract.get_pending_reward("ATOM-REWARD-3".to_string()), None);
+        contract.verify_atom("ATOM-REWARD-3".to_string());
+        assert_eq!(contract.get_claimable_rewards(alice.clone()), U128(0));
+
+        // Amended above the threshold: the reward is held at the new coherence
+        contract.amend_atom(
+            "ATOM-REWARD-3".to_string(),
+            make_atom("ATOM-REWARD-3", "QDI", "alice.near", 85, &[]),
+        );
+        assert_eq!(contract.get_pending_reward("ATOM-REWARD-3".to_string()), Some(U128(1_500)));
+        contract.verify_atom("ATOM-REWARD-3".to_string());
+        assert_eq!(contract.get_claimable_rewards(alice.clone()), U128(1_500));
+
+        // Once paid, later amendments never hold it again
+        contract.amend_atom(
+            "ATOM-REWARD-3".to_string(),
+            make_atom("ATOM-REWARD-3", "QDI", "alice.near", 100, &[]),
+        );
+        assert_eq!(contract.get_pending_reward("ATOM-REWARD-3".to_string()), None);
+        contract.verify_atom("ATOM-REWARD-3".to_string());
+        assert_eq!(contract.get_claimable_rewards(alice), U128(1_500));
+    }
+
+    #[test]
+    fn test_self_attested_atom() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.record_self_attested_atom(
+            make_atom("ATOM-SIGNED-1", "QDI", "alice", 80, &[]),
+            Base64VecU8::from(TEST_SIGNATURE.to_vec()),
+            Base64VecU8::from(TEST_PUBLIC_KEY.to_vec()),
+        );
+
+        assert!(contract.get_atom("ATOM-SIGNED-1".to_string()).is_some());
+        let key = contract.get_attestation_key("ATOM-SIGNED-1".to_string()).unwrap();
+        assert_eq!(key.0, TEST_PUBLIC_KEY.to_vec());
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid self-attestation signature")]
+    fn test_self_attested_atom_rejects_inflated_score() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+
+        // Signature covers a score of 80, not 95
+        contract.record_self_attested_atom(
+            make_atom("ATOM-SIGNED-1", "QDI", "alice", 95, &[]),
+            Base64VecU8::from(TEST_SIGNATURE.to_vec()),
+            Base64VecU8::from(TEST_PUBLIC_KEY.to_vec()),
+        );
+    }
+
+    #[test]
+    fn test_dedup_markers_and_phases() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        let mut atom = make_atom("ATOM-DUP-1", "QDI", "alice", 80, &["WAVE", "WAVE", "PASS"]);
+        atom.phases_passed = vec!["KENL".to_string(), "AWI".to_string(), "KENL".to_string()];
+        contract.record_atom(atom);
+
+        let stored = contract.get_atom("ATOM-DUP-1".to_string()).unwrap();
+        assert_eq!(stored.markers, vec!["WAVE".to_string(), "PASS".to_string()]);
+        assert_eq!(stored.phases_passed, vec!["KENL".to_string(), "AWI".to_string()]);
+
+        let mut phases = contract.get_phase_stats();
+        phases.sort();
+        assert_eq!(phases, vec![("AWI".to_string(), 1), ("KENL".to_string(), 1)]);
+
+        let (_, _, markers) = contract.get_attribution("alice".to_string());
+        assert_eq!(markers, vec!["WAVE".to_string(), "PASS".to_string()]);
+    }
+
+    #[test]
+    fn test_contributor_snap_in_repos() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.record_atom(make_atom("ATOM-SNAP-1", "QDI", "alice", 90, &[]));
+        contract.record_atom(make_atom("ATOM-SNAP-2", "QDI", "alice", 85, &[]));
+        contract.record_atom(make_atom("ATOM-SNAP-3", "KENL", "alice", 40, &[]));
+        contract.record_atom(make_atom("ATOM-SNAP-4", "KENL", "alice", 65, &[]));
+
+        assert_eq!(
+            contract.get_contributor_snap_in_repos("alice".to_string()),
+            vec!["qdi".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_repo_ranking_pages() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.record_atom(make_atom("ATOM-RANK-1", "QDI", "alice", 85, &[]));
+        contract.record_atom(make_atom("ATOM-RANK-2", "KENL", "alice", 75, &[]));
+        contract.record_atom(make_atom("ATOM-RANK-3", "AWI", "alice", 50, &[]));
+
+        let first = contract.get_repo_ranking(0, 2);
+        let second = contract.get_repo_ranking(2, 2);
+        assert_eq!(first.len(), 2);
+        assert_eq!(second.len(), 1);
+
+        let mut rows: Vec<RepoState> = first.into_iter().chain(second).collect();
+        rows.sort_by_key(|s| std::cmp::Reverse(s.average_coherence));
+        let ranked: Vec<(String, u64)> = rows.into_iter().map(|s| (s.repo, s.atom_count)).collect();
+        assert_eq!(
+            ranked,
+            vec![
+                ("qdi".to_string(), 1),
+                ("kenl".to_string(), 1),
+                ("awi".to_string(), 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_repos_below_threshold() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.set_repo_snap_in_threshold("QDI".to_string(), Some(90));
+        contract.record_atom(make_atom("ATOM-RISK-1", "QDI", "alice", 85, &[]));
+        contract.record_atom(make_atom("ATOM-RISK-2", "KENL", "alice", 75, &[]));
+        contract.record_atom(make_atom("ATOM-RISK-3", "AWI", "alice", 50, &[]));
+
+        let mut at_risk = contract.get_repos_below_threshold();
+        at_risk.sort();
+        assert_eq!(at_risk, vec!["awi".to_string(), "qdi".to_string()]);
+
+        // QDI's 85 is not a snap-in under its own threshold
+        assert_eq!(contract.get_vortex_state().snap_in_count, 1);
+    }
+
+    #[test]
+    fn test_most_improved_repos() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+
+        // QDI: 40 -> 70 (+30), KENL: 50 -> 60 (+10), AWI: 80 -> 60 (-20)
+        contract.record_atom(make_atom("ATOM-UP-1", "QDI", "alice", 40, &[]));
+        contract.record_atom(make_atom("ATOM-UP-2", "QDI", "alice", 100, &[]));
+        contract.record_atom(make_atom("ATOM-UP-3", "KENL", "alice", 50, &[]));
+        contract.record_atom(make_atom("ATOM-UP-4", "KENL", "alice", 70, &[]));
+        contract.record_atom(make_atom("ATOM-UP-5", "AWI", "alice", 80, &[]));
+        contract.record_atom(make_atom("ATOM-UP-6", "AWI", "alice", 40, &[]));
+
+        assert_eq!(contract.get_coherence_series("QDI".to_string()), vec![40, 70]);
+        assert_eq!(
+            contract.get_most_improved_repos(5, 10, 10),
+            vec![("qdi".to_string(), 30), ("kenl".to_string(), 10)]
+        );
+        assert_eq!(
+            contract.get_most_improved_repos(5, 1, 10),
+            vec![("qdi".to_string(), 30)]
+        );
+        // An oversized window just spans the whole series
+        assert_eq!(
+            contract.get_most_improved_repos(u32::MAX, 1, 10),
+            vec![("qdi".to_string(), 30)]
+        );
+    }
+
+    #[test]
+    fn test_amend_atom_history() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.record_atom(make_atom("ATOM-AMEND-1", "QDI", "alice", 40, &[]));
+        contract.record_atom(make_atom("ATOM-AMEND-2", "QDI", "alice", 80, &[]));
+
+        let mut fixed = make_atom("ATOM-AMEND-1", "QDI", "alice", 60, &[]);
+        fixed.commit_hash = "def4567".to_string();
+        contract.amend_atom("ATOM-AMEND-1".to_string(), fixed);
+
+        let rescored = make_atom("ATOM-AMEND-1", "QDI", "alice", 100, &[]);
+        contract.amend_atom("ATOM-AMEND-1".to_string(), rescored);
+
+        let history = contract.get_atom_history("ATOM-AMEND-1".to_string());
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].coherence_score, 40);
+        assert_eq!(history[1].coherence_score, 60);
+        assert_eq!(history[1].commit_hash, "def4567");
+
+        let current = contract.get_atom("ATOM-AMEND-1".to_string()).unwrap();
+        assert_eq!(current.coherence_score, 100);
+        assert_eq!(current.sequence, 0);
+
+        // (100 + 80) / 2 across the repo and the ecosystem
+        assert_eq!(contract.get_repo_state("QDI".to_string()).unwrap().average_coherence, 90);
+        assert_eq!(contract.get_vortex_state().average_coherence, 90);
+        assert_eq!(contract.get_contributor_stats("alice".to_string()).unwrap().coherence_sum, 180);
+    }
+
+    #[test]
+    fn test_amend_atom_moves_phase_and_snap_in_counts() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        let mut atom = make_atom("ATOM-AMEND-3", "QDI", "alice", 40, &[]);
+        atom.phases_passed = vec!["KENL".to_string(), "AWI".to_string()];
+        contract.record_atom(atom);
+        assert_eq!(contract.get_contributor_stats("alice".to_string()).unwrap().snap_in_count, 0);
+
+        let mut fixed = make_atom("ATOM-AMEND-3", "QDI", "alice", 80, &[]);
+        fixed.phases_passed = vec!["KENL".to_string(), "ATOM".to_string()];
+        contract.amend_atom("ATOM-AMEND-3".to_string(), fixed);
+
+        let expected = vec![("ATOM".to_string(), 1), ("KENL".to_string(), 1)];
+        let mut global = contract.get_phase_stats();
+        global.sort();
+        assert_eq!(global, expected);
+        let mut repo = contract.get_repo_phase_stats("QDI".to_string());
+        repo.sort();
+        assert_eq!(repo, expected);
+        assert_eq!(contract.get_contributor_stats("alice".to_string()).unwrap().snap_in_count, 1);
+    }
+
+    #[test]
+    fn test_marker_weighted_average() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.set_marker_weight("WAVE".to_string(), 3);
+        contract.set_weighted_averages(true);
+
+        contract.record_atom(make_atom("ATOM-WEIGHT-1", "QDI", "alice", 90, &["WAVE"]));
+        contract.record_atom(make_atom("ATOM-WEIGHT-2", "QDI", "alice", 30, &[]));
+
+        // (90 * 3 + 30 * 1) / 4 weighted, (90 + 30) / 2 raw
+        let state = contract.get_repo_state("QDI".to_string()).unwrap();
+        assert_eq!(state.weighted_average_coherence, 75);
+        assert_eq!(state.average_coherence, 60);
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds max batch size")]
+    fn test_batch_record_atoms_size_limit() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        let atoms = (0..=contract.max_batch_size())
+            .map(|i| make_atom(&format!("ATOM-BULK-{}", i), "QDI", "alice", 80, &[]))
+            .collect();
+        contract.batch_record_atoms(atoms);
+    }
+
+    #[test]
+    fn test_contributor_band_histogram() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.record_atom(make_atom("ATOM-BAND-1", "QDI", "low", 20, &[]));
+        contract.record_atom(make_atom("ATOM-BAND-2", "QDI", "low", 40, &[]));
+        contract.record_atom(make_atom("ATOM-BAND-3", "QDI", "medium", 60, &[]));
+        contract.record_atom(make_atom("ATOM-BAND-4", "QDI", "snapped", 90, &[]));
+
+        assert_eq!(contract.get_contributor_band_histogram(), [1, 1, 1]);
+    }
+
+    #[test]
+    fn test_linked_contributor_attribution() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.record_atom(make_atom("ATOM-ALIAS-1", "QDI", "toolate28", 80, &["WAVE"]));
+        contract.record_atom(make_atom("ATOM-ALIAS-2", "QDI", "toolate28.near", 60, &["PASS"]));
+
+        contract.link_contributor("toolate28".to_string(), "toolate28.near".to_string(), 10);
+        contract.record_atom(make_atom("ATOM-ALIAS-3", "QDI", "toolate28", 100, &["WAVE"]));
+
+        // The alias's earlier trail and its new ATOMs both roll up to the canonical identity
+        let (count, avg, markers) = contract.get_attribution("toolate28.near".to_string());
+        assert_eq!(count, 3);
+        assert_eq!(avg, 80);
+        assert_eq!(markers.len(), 3);
+        assert_eq!(contract.get_attribution("toolate28".to_string()).0, 3);
+        assert_eq!(contract.get_contributor_atoms("toolate28".to_string()).len(), 3);
+        assert_eq!(
+            contract.get_canonical_contributor("toolate28".to_string()),
+            "toolate28.near"
+        );
+    }
+
+    #[test]
+    fn test_link_contributor_merges_existing_alias() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.record_atom(make_atom("ATOM-MERGE-1", "QDI", "gh-handle", 80, &[]));
+        contract.record_atom(make_atom("ATOM-MERGE-2", "KENL", "gh-handle", 40, &[]));
+        contract.record_atom(make_atom("ATOM-MERGE-3", "QDI", "toolate28.near", 60, &[]));
+        assert_eq!(contract.get_vortex_state().contributor_count, 2);
+        assert_eq!(contract.get_repo_state("QDI".to_string()).unwrap().contributor_count, 2);
+
+        contract.link_contributor("gh-handle".to_string(), "toolate28.near".to_string(), 10);
+
+        // One person everywhere: stats, leaderboard, bands and repo counts
+        let stats = contract.get_contributor_stats("gh-handle".to_string()).unwrap();
+        assert_eq!(stats.atom_count, 3);
+        assert_eq!(stats.coherence_sum, 180);
+        assert_eq!(stats.snap_in_count, 1);
+        assert!(contract.get_contributor_stats("toolate28.near".to_string()) == Some(stats));
+        assert_eq!(contract.get_attribution("gh-handle".to_string()).0, 3);
+        assert_eq!(
+            contract.get_contributor_leaderboard(10, 10),
+            vec![("toolate28.near".to_string(), 60, 3)]
+        );
+        assert_eq!(contract.get_contributor_band_histogram(), [0, 1, 0]);
+        assert_eq!(contract.get_repo_state("QDI".to_string()).unwrap().contributor_count, 1);
+        assert_eq!(contract.get_repo_state("KENL".to_string()).unwrap().contributor_count, 1);
+        assert_eq!(contract.get_vortex_state().contributor_count, 1);
+
+        // Invalidating a pre-link alias ATOM only removes that ATOM
+        contract.flag_atom("ATOM-MERGE-1".to_string(), "duplicate run".to_string());
+        contract.invalidate_atom("ATOM-MERGE-1".to_string());
+        let stats = contract.get_contributor_stats("toolate28.near".to_string()).unwrap();
+        assert_eq!(stats.atom_count, 2);
+        assert_eq!(stats.coherence_sum, 100);
+        assert_eq!(stats.snap_in_count, 0);
+
+        // A canonical identity first seen through its alias is not counted again
+        contract.record_atom(make_atom("ATOM-MERGE-4", "QDI", "bob-gh", 70, &[]));
+        contract.link_contributor("bob-gh".to_string(), "bob.near".to_string(), 10);
+        contract.record_atom(make_atom("ATOM-MERGE-5", "QDI", "bob.near", 90, &[]));
+        assert_eq!(contract.get_vortex_state().contributor_count, 2);
+        assert_eq!(contract.get_repo_state("QDI".to_string()).unwrap().contributor_count, 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "Canonical identity cannot itself be an alias")]
+    fn test_link_contributor_rejects_chains() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.link_contributor("gh-handle".to_string(), "toolate28".to_string(), 10);
+        contract.link_contributor("toolate28.near".to_string(), "gh-handle".to_string(), 10);
+    }
+
+    #[test]
+    fn test_record_atom_with_repo_meta() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.record_atom_with_repo_meta(
+            make_atom("ATOM-META-1", "QDI", "alice", 80, &[]),
+            Some("Quantum Decision Index".to_string()),
+        );
+
+        let state = contract.get_repo_state("QDI".to_string()).unwrap();
+        assert_eq!(state.atom_count, 1);
+        assert_eq!(state.display_name, Some("Quantum Decision Index".to_string()));
+
+        // Existing repos keep their name, under any casing
+        contract.record_atom_with_repo_meta(
+            make_atom("ATOM-META-2", " qdi", "alice", 80, &[]),
+            Some("Renamed".to_string()),
+        );
+        let state = contract.get_repo_state("QDI".to_string()).unwrap();
+        assert_eq!(state.atom_count, 2);
+        assert_eq!(state.display_name, Some("Quantum Decision Index".to_string()));
+    }
+
+    #[test]
+    fn test_storage_stats() {
+        near_sdk::testing_env!(near_sdk::test_utils::VMContextBuilder::new()
+            .predecessor_account_id("owner.near".parse().unwrap())
+            .storage_usage(10_000)
+            .build());
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.record_atom(make_atom("ATOM-STORE-1", "QDI", "alice", 80, &[]));
+
+        let stats = contract.get_storage_stats();
+        assert!(stats.storage_usage > 10_000);
+        assert!(stats.storage_byte_cost.0 > 0);
+        assert_eq!(
+            stats.storage_cost.0,
+            stats.storage_usage as u128 * stats.storage_byte_cost.0
+        );
+        assert_eq!(stats.total_atoms, 1);
+    }
+
+    #[test]
+    fn test_repo_first_snap_in_atom() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.record_atom(make_atom("ATOM-CROSS-1", "QDI", "alice", 50, &[]));
+        contract.record_atom(make_atom("ATOM-CROSS-2", "QDI", "alice", 60, &[]));
+        assert!(contract.get_repo_first_snap_in_atom("QDI".to_string()).is_none());
+
+        // Average goes 50 -> 55 -> 70
+        contract.record_atom(make_atom("ATOM-CROSS-3", "QDI", "alice", 100, &[]));
+        contract.record_atom(make_atom("ATOM-CROSS-4", "QDI", "alice", 100, &[]));
+
+        let crossing = contract.get_repo_first_snap_in_atom("QDI".to_string()).unwrap();
+        assert_eq!(crossing.atom_tag, "ATOM-CROSS-3");
+    }
+
+    #[test]
+    fn test_record_atom_idempotent() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        let key = Some("upload-42".to_string());
+
+        let first = contract.record_atom_idempotent(
+            make_atom("ATOM-RETRY-1", "QDI", "alice", 80, &[]),
+            key.clone(),
+        );
+        let retry = contract.record_atom_idempotent(
+            make_atom("ATOM-RETRY-1", "QDI", "alice", 80, &[]),
+            key,
+        );
+
+        assert_eq!(first, retry);
+        assert_eq!(contract.get_vortex_state().total_atoms, 1);
+        assert_eq!(contract.get_repo_state("QDI".to_string()).unwrap().atom_count, 1);
+    }
+
+    #[test]
+    fn test_max_total_atoms_raised() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.set_max_total_atoms(Some(2));
+        contract.record_atom(make_atom("ATOM-CAP-1", "QDI", "alice", 80, &[]));
+        contract.record_atom(make_atom("ATOM-CAP-2", "QDI", "alice", 80, &[]));
+
+        assert_eq!(contract.get_capacity(), (2, Some(2)));
+
+        contract.set_max_total_atoms(Some(3));
+        contract.record_atom(make_atom("ATOM-CAP-3", "QDI", "alice", 80, &[]));
+        assert_eq!(contract.get_capacity(), (3, Some(3)));
+
+        contract.set_max_total_atoms(None);
+        contract.record_atom(make_atom("ATOM-CAP-4", "QDI", "alice", 80, &[]));
+        assert_eq!(contract.get_capacity(), (4, None));
+    }
+
+    #[test]
+    #[should_panic(expected = "ecosystem capacity reached")]
+    fn test_max_total_atoms_rejects_record() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.set_max_total_atoms(Some(2));
+        contract.record_atom(make_atom("ATOM-CAP-1", "QDI", "alice", 80, &[]));
+        contract.record_atom(make_atom("ATOM-CAP-2", "QDI", "alice", 80, &[]));
+        contract.record_atom(make_atom("ATOM-CAP-3", "QDI", "alice", 80, &[]));
+    }
+
+    #[test]
+    #[should_panic(expected = "ecosystem capacity reached")]
+    fn test_max_total_atoms_counts_invalidated() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.set_max_total_atoms(Some(1));
+        contract.record_atom(make_atom("ATOM-CAP-1", "QDI", "alice", 80, &[]));
+        contract.flag_atom("ATOM-CAP-1".to_string(), "fraudulent run".to_string());
+        contract.invalidate_atom("ATOM-CAP-1".to_string());
+        assert_eq!(contract.get_capacity(), (1, Some(1)));
+
+        contract.record_atom(make_atom("ATOM-CAP-2", "QDI", "alice", 80, &[]));
+    }
+
+    #[test]
+    #[should_panic(expected = "ecosystem capacity reached")]
+    fn test_max_total_atoms_rejects_batch() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.set_max_total_atoms(Some(2));
+        contract.batch_record_atoms(vec![
+            make_atom("ATOM-CAP-1", "QDI", "alice", 80, &[]),
+            make_atom("ATOM-CAP-2", "QDI", "alice", 80, &[]),
+            make_atom("ATOM-CAP-3", "QDI", "alice", 80, &[]),
+        ]);
+    }
+
+    #[test]
+    fn test_admin_can_tune_thresholds() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.add_admin("ops.near".parse().unwrap());
+        assert!(contract.is_admin("ops.near".parse().unwrap()));
+        assert!(!contract.is_admin("alice.near".parse().unwrap()));
+
+        set_caller("ops.near");
+        contract.set_snap_in_threshold(60);
+        contract.set_repo_snap_in_threshold("QDI".to_string(), Some(80));
+        contract.set_marker_weight("security".to_string(), 3);
+        assert_eq!(contract.get_effective_threshold("QDI".to_string()), 80);
+        assert_eq!(contract.get_effective_threshold("coherence-mcp".to_string()), 60);
+        assert_eq!(contract.get_marker_weight("security".to_string()), Some(3));
+    }
+
+    #[test]
+    #[should_panic(expected = "Only owner")]
+    fn test_admin_cannot_manage_admins() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.add_admin("ops.near".parse().unwrap());
+
+        set_caller("ops.near");
+        contract.add_admin("mallory.near".parse().unwrap());
+    }
+
+    #[test]
+    #[should_panic(expected = "Only owner or admin")]
+    fn test_removed_admin_cannot_tune_thresholds() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.add_admin("ops.near".parse().unwrap());
+        contract.remove_admin("ops.near".parse().unwrap());
+        assert!(!contract.is_admin("ops.near".parse().unwrap()));
+
+        set_caller("ops.near");
+        contract.set_snap_in_threshold(60);
+    }
+
+    #[test]
+    fn test_nested_prefixes_do_not_collide() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        // A contributor named like another collection's old prefix keeps its own trail
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.record_atom(make_atom("ATOM-KEY-1", "KENL", "i:qdi", 80, &[]));
+        contract.record_atom(make_atom("ATOM-KEY-2", "QDI", "alice", 80, &[]));
+
+        let trail = contract.get_contributor_atoms("i:qdi".to_string());
+        let tags: Vec<&str> = trail.iter().map(|atom| atom.atom_tag.as_str()).collect();
+        assert_eq!(tags, vec!["ATOM-KEY-1"]);
+        let repo = contract.get_repo_atoms("QDI".to_string(), 10, None);
+        assert_eq!(repo.len(), 1);
+        assert_eq!(repo[0].atom_tag, "ATOM-KEY-2");
+    }
+
+    #[test]
+    fn test_contributor_atoms_recent() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        for i in 0..10 {
+            let tag = format!("ATOM-RECENT-{}", i);
+            contract.record_atom(make_atom(&tag, "QDI", "alice", 80, &[]));
+        }
+
+        let recent = contract.get_contributor_atoms_recent("alice".to_string(), 3);
+        let tags: Vec<String> = recent.iter().map(|atom| atom.atom_tag.clone()).collect();
+        assert_eq!(tags, vec!["ATOM-RECENT-9", "ATOM-RECENT-8", "ATOM-RECENT-7"]);
+
+        assert_eq!(contract.get_contributor_atoms_recent("alice".to_string(), 50).len(), 10);
+        assert!(contract.get_contributor_atoms_recent("bob".to_string(), 3).is_empty());
+    }
+
+    #[test]
+    fn test_contributor_atoms_recent_across_aliases() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.record_atom(make_atom("ATOM-RECENT-A1", "QDI", "gh-handle", 80, &[]));
+        contract.record_atom(make_atom("ATOM-RECENT-C1", "QDI", "toolate28.near", 80, &[]));
+        contract.record_atom(make_atom("ATOM-RECENT-A2", "QDI", "gh-handle", 80, &[]));
+        contract.link_contributor("gh-handle".to_string(), "toolate28.near".to_string(), 10);
+        contract.record_atom(make_atom("ATOM-RECENT-C2", "QDI", "gh-handle", 80, &[]));
+
+        let recent = contract.get_contributor_atoms_recent("toolate28.near".to_string(), 3);
+        let tags: Vec<String> = recent.iter().map(|atom| atom.atom_tag.clone()).collect();
+        assert_eq!(tags, vec!["ATOM-RECENT-C2", "ATOM-RECENT-A2", "ATOM-RECENT-C1"]);
+        assert_eq!(contract.get_contributor_atoms_recent("gh-handle".to_string(), 10).len(), 4);
+    }
+
+    #[test]
+    fn test_repo_trend_since_snapshot() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.record_atom(make_atom("ATOM-TREND-1", "QDI", "alice", 40, &[]));
+        contract.record_atom(make_atom("ATOM-TREND-2", "KENL", "alice", 60, &[]));
+        assert_eq!(contract.snapshot_repo_coherence(0, 1), 1);
+        assert_eq!(contract.get_repo_trend("QDI".to_string()), (40, 0));
+        assert_eq!(contract.get_repo_trend("KENL".to_string()), (60, 60));
+        assert_eq!(contract.snapshot_repo_coherence(1, 10), 2);
+        assert_eq!(contract.get_repo_trend("KENL".to_string()), (60, 0));
+
+        contract.record_atom(make_atom("ATOM-TREND-3", "QDI", "alice", 100, &[]));
+        assert_eq!(contract.get_repo_trend("QDI".to_string()), (70, 30));
+        assert_eq!(contract.get_repo_trend("unknown".to_string()), (0, 0));
+    }
+
+    fn set_balance(balance: NearToken) {
+        near_sdk::testing_env!(near_sdk::test_utils::VMContextBuilder::new()
+            .predecessor_account_id("owner.near".parse().unwrap())
+            .account_balance(balance)
+            .build());
+    }
+
+    #[test]
+    fn test_withdraw_surplus() {
+        set_balance(NearToken::from_near(10));
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        let amount = U128(NearToken::from_near(5).as_yoctonear());
+        let _ = contract.withdraw(amount, "treasury.near".parse().unwrap());
+
+        let receipts = near_sdk::test_utils::get_created_receipts();
+        let transfer = receipts
+            .iter()
+            .find(|r| r.receiver_id.as_str() == "treasury.near")
+            .expect("No receipt to the receiver");
+        match &transfer.actions[0] {
+            near_sdk::mock::MockAction::Transfer { deposit, .. } => {
+                assert_eq!(*deposit, NearToken::from_near(5));
+            }
+            _ => panic!("Expected a transfer"),
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Withdrawal would drop below the storage reserve")]
+    fn test_withdraw_protects_storage_reserve() {
+        set_balance(NearToken::from_near(10));
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.record_atom(make_atom("ATOM-WITHDRAW-1", "QDI", "alice", 80, &[]));
+        let amount = U128(NearToken::from_near(10).as_yoctonear());
+        let _ = contract.withdraw(amount, "treasury.near".parse().unwrap());
+    }
+
+    #[test]
+    fn test_field_lengths_at_limits() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        let tag = "T".repeat(MAX_ATOM_TAG_LEN);
+        let repo = "r".repeat(MAX_REPO_LEN);
+        let contributor = "c".repeat(MAX_CONTRIBUTOR_LEN);
+        let mut atom = make_atom(&tag, &repo, &contributor, 80, &[]);
+        atom.commit_hash = "f".repeat(MAX_COMMIT_HASH_LEN);
+
+        contract.record_atom(atom);
+        assert!(contract.get_atom(tag).is_some());
+    }
+
+    #[test]
+    #[should_panic(expected = "ATOM tag exceeds")]
+    fn test_rejects_oversized_tag() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        let atom = make_atom(&"T".repeat(MAX_ATOM_TAG_LEN + 1), "QDI", "alice", 80, &[]);
+        contract.record_atom(atom);
+    }
+
+    #[test]
+    #[should_panic(expected = "Repo exceeds")]
+    fn test_rejects_oversized_repo() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        let atom = make_atom("ATOM-LEN-1", &"r".repeat(MAX_REPO_LEN + 1), "alice", 80, &[]);
+        contract.record_atom(atom);
+    }
+
+    #[test]
+    #[should_panic(expected = "Contributor exceeds")]
+    fn test_rejects_oversized_contributor() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        let atom = make_atom("ATOM-LEN-1", "QDI", &"c".repeat(MAX_CONTRIBUTOR_LEN + 1), 80, &[]);
+        contract.record_atom(atom);
+    }
+
+    #[test]
+    #[should_panic(expected = "Commit hash exceeds")]
+    fn test_rejects_oversized_commit_hash() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        let mut atom = make_atom("ATOM-LEN-1", "QDI", "alice", 80, &[]);
+        atom.commit_hash = "f".repeat(MAX_COMMIT_HASH_LEN + 1);
+        contract.record_atom(atom);
+    }
+
+    #[test]
+    #[should_panic(expected = "Repo required")]
+    fn test_rejects_empty_repo() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        let atom = make_atom("ATOM-LEN-1", "", "alice", 80, &[]);
+        contract.record_atom(atom);
+    }
+
+    #[test]
+    #[should_panic(expected = "Contributor required")]
+    fn test_rejects_empty_contributor() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        let atom = make_atom("ATOM-LEN-1", "QDI", "", 80, &[]);
+        contract.record_atom(atom);
+    }
+
+    fn pipeline_phases() -> Vec<String> {
+        vec!["KENL".to_string(), "AWI".to_string(), "ATOM".to_string()]
+    }
+
+    #[test]
+    fn test_phase_registry_accepts_ordered_prefix() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.set_phase_registry(pipeline_phases());
+        contract.set_phase_order_enforced(true);
+        assert_eq!(contract.get_phase_registry(), pipeline_phases());
+
+        let mut atom = make_atom("ATOM-PHASE-1", "QDI", "alice", 80, &[]);
+        atom.phases_passed = vec!["KENL".to_string(), "AWI".to_string()];
+        contract.record_atom(atom);
+
+        let mut atom = make_atom("ATOM-PHASE-2", "QDI", "alice", 80, &[]);
+        atom.phases_passed = pipeline_phases();
+        contract.record_atom(atom);
+        assert_eq!(contract.get_vortex_state().total_atoms, 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unknown phase KENLL")]
+    fn test_phase_registry_rejects_unknown() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.set_phase_registry(pipeline_phases());
+        contract.set_phase_order_enforced(false);
+
+        let mut atom = make_atom("ATOM-PHASE-1", "QDI", "alice", 80, &[]);
+        atom.phases_passed = vec!["KENLL".to_string()];
+        contract.record_atom(atom);
+    }
+
+    #[test]
+    #[should_panic(expected = "Phases must follow the pipeline order")]
+    fn test_phase_registry_rejects_out_of_order() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.set_phase_registry(pipeline_phases());
+        contract.set_phase_order_enforced(true);
+
+        let mut atom = make_atom("ATOM-PHASE-1", "QDI", "alice", 80, &[]);
+        atom.phases_passed = vec!["KENL".to_string(), "ATOM".to_string()];
+        contract.record_atom(atom);
+    }
+
+    #[test]
+    fn test_atom_detail() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.record_atom(make_atom("ATOM-DETAIL-1", "QDI", "alice", 80, &[]));
+        contract.record_atom(make_atom("ATOM-DETAIL-2", "QDI", "alice", 50, &[]));
+        contract.flag_atom("ATOM-DETAIL-2".to_string(), "Score looks inflated".to_string());
+
+        let above = contract.get_atom_detail("ATOM-DETAIL-1".to_string()).unwrap();
+        assert!(above.met_threshold);
+        assert_eq!(above.repo_average_coherence, 65);
+        assert!(above.flag.is_none());
+
+        let below = contract.get_atom_detail("ATOM-DETAIL-2".to_string()).unwrap();
+        assert!(!below.met_threshold);
+        assert_eq!(below.flag.unwrap().reason, "Score looks inflated");
+
+        assert!(contract.get_atom_detail("ATOM-MISSING".to_string()).is_none());
+    }
+
+    #[test]
+    fn test_repo_names_normalized() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.record_atom(make_atom("ATOM-CASE-1", "QDI", "alice", 60, &[]));
+        contract.record_atom(make_atom("ATOM-CASE-2", "qdi", "alice", 80, &[]));
+        contract.record_atom(make_atom("ATOM-CASE-3", " Qdi ", "alice", 100, &[]));
+
+        assert_eq!(contract.get_repo_coherence(), vec![("qdi".to_string(), 80)]);
+        let state = contract.get_repo_state("Qdi".to_string()).unwrap();
+        assert_eq!(state.repo, "qdi");
+        assert_eq!(state.atom_count, 3);
+        assert_eq!(contract.get_repo_atoms("QDI ".to_string(), 10, None).len(), 3);
+        assert_eq!(contract.get_atom("ATOM-CASE-3".to_string()).unwrap().repo, "qdi");
+    }
+
+    #[test]
+    fn test_uploader_amends_and_invalidates_own_atom() {
+        set_caller("uploader.near");
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.record_atom(make_atom("ATOM-OWN-1", "QDI", "alice", 80, &[]));
+        assert_eq!(
+            contract.get_atom_recorder("ATOM-OWN-1".to_string()),
+            Some("uploader.near".parse().unwrap())
+        );
+
+        contract.amend_atom(
+            "ATOM-OWN-1".to_string(),
+            make_atom("ATOM-OWN-1", "QDI", "alice", 60, &[]),
+        );
+        contract.flag_atom("ATOM-OWN-1".to_string(), "Wrong commit".to_string());
+        contract.invalidate_atom("ATOM-OWN-1".to_string());
+
+        let atom = contract.get_atom("ATOM-OWN-1".to_string()).unwrap();
+        assert_eq!(atom.coherence_score, 60);
+        assert!(atom.invalid);
+        assert!(contract.get_atom_recorder("ATOM-MISSING".to_string()).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the uploader or owner can amend an ATOM")]
+    fn test_amend_rejects_foreign_uploader() {
+        set_caller("uploader.near");
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.record_atom(make_atom("ATOM-OWN-1", "QDI", "alice", 80, &[]));
+
+        set_caller("other-uploader.near");
+        contract.amend_atom(
+            "ATOM-OWN-1".to_string(),
+            make_atom("ATOM-OWN-1", "QDI", "alice", 100, &[]),
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the uploader or owner can invalidate an ATOM")]
+    fn test_invalidate_rejects_foreign_uploader() {
+        set_caller("uploader.near");
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.record_atom(make_atom("ATOM-OWN-1", "QDI", "alice", 80, &[]));
+        contract.flag_atom("ATOM-OWN-1".to_string(), "Wrong commit".to_string());
+
+        set_caller("other-uploader.near");
+        contract.invalidate_atom("ATOM-OWN-1".to_string());
+    }
+
+    #[test]
+    fn test_atoms_by_marker_pages() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.record_atom(make_atom("ATOM-MK-1", "QDI", "alice", 40, &["FAIL"]));
+        contract.record_atom(make_atom("ATOM-MK-2", "KENL", "bob", 90, &["PASS"]));
+        contract.record_atom(make_atom("ATOM-MK-3", "AWI", "alice", 30, &["FAIL", "WAVE"]));
+        contract.record_atom(make_atom("ATOM-MK-4", "QDI", "carol", 20, &["WAVE", "FAIL"]));
+
+        let tags = |atoms: Vec<ATOMOnChain>| -> Vec<String> {
+            atoms.into_iter().map(|atom| atom.atom_tag).collect()
+        };
+        let first = contract.get_atoms_by_marker("FAIL".to_string(), 0, 2);
+        assert_eq!(tags(first), vec!["ATOM-MK-1", "ATOM-MK-3"]);
+        let second = contract.get_atoms_by_marker("FAIL".to_string(), 2, 2);
+        assert_eq!(tags(second), vec!["ATOM-MK-4"]);
+        assert_eq!(contract.get_atoms_by_marker("WAVE".to_string(), 0, 10).len(), 2);
+        assert!(contract.get_atoms_by_marker("NONE".to_string(), 0, 10).is_empty());
     }
-}
 
-// ==================== TESTS ====================
+    #[test]
+    fn test_batch_update_coherence_skips_unknown() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.record_atom(make_atom("ATOM-BU-1", "QDI", "alice", 40, &[]));
+        contract.record_atom(make_atom("ATOM-BU-2", "KENL", "alice", 60, &[]));
 
-    fn get_context() -> near_sdk::VMContext {
-        near_sdk::test_utils::VMContextBuilder::new()
-            .predecessor_account_id("owner.near".parse().unwrap())
-            .build()
+        let skipped = contract.batch_update_coherence(vec![
+            ("QDI".to_string(), 80),
+            ("ghost".to_string(), 90),
+            ("KENL".to_string(), 100),
+        ]);
+        assert_eq!(skipped, vec!["ghost".to_string()]);
+        assert_eq!(contract.get_repo_state("QDI".to_string()).unwrap().average_coherence, 80);
+        assert_eq!(contract.get_repo_state("KENL".to_string()).unwrap().average_coherence, 100);
+        assert_eq!(contract.get_vortex_state().average_coherence, 90);
+    }
+
+    // Write state the way the baseline contract laid it out
+    fn write_baseline_state(atoms: &[(&str, &str, &str, u8)]) {
+        let mut old = OldState {
+            atoms: LookupMap::new(b"a"),
+            repos: UnorderedMap::new(b"r"),
+            contributor_atoms: LookupMap::new(b"c"),
+            vortex_state: VortexStateV0 {
+                total_atoms: 0,
+                average_coherence: 0,
+                snap_in_count: 0,
+                last_update: "0".to_string(),
+            },
+            owner: "owner.near".parse().unwrap(),
+            snap_in_threshold: 60,
+        };
+        for (tag, repo, contributor, coherence) in atoms {
+            old.atoms.insert(
+                &tag.to_string(),
+                &ATOMOnChainV0 {
+                    atom_tag: tag.to_string(),
+                    repo: repo.to_string(),
+                    coherence_score: *coherence,
+                    phases_passed: vec!["KENL".to_string()],
+                    markers: vec!["WAVE".to_string()],
+                    contributor: contributor.to_string(),
+                    timestamp: "2026-01-17T00:00:00Z".to_string(),
+                    commit_hash: "abc1234".to_string(),
+                    pr_number: None,
+                },
+            );
+            let mut state = old.repos.get(&repo.to_string()).unwrap_or(RepoStateV0 {
+                repo: repo.to_string(),
+                atom_count: 0,
+                total_coherence: 0,
+                average_coherence: 0,
+                last_snap_in: None,
+            });
+            state.atom_count += 1;
+            state.total_coherence += *coherence as u64;
+            old.repos.insert(&repo.to_string(), &state);
+            let mut trail = old
+                .contributor_atoms
+                .get(&contributor.to_string())
+                .unwrap_or_else(|| Vector::new(contributor.as_bytes()));
+            trail.push(&tag.to_string());
+            old.contributor_atoms.insert(&contributor.to_string(), &trail);
+            old.vortex_state.total_atoms += 1;
+        }
+        env::state_write(&old);
     }
 
     #[test]
-    fn test_record_atom() {
+    fn test_migrate_from_baseline_layout() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+        write_baseline_state(&[
+            ("ATOM-OLD-1", "QDI", "alice", 80),
+            ("ATOM-OLD-2", "QDI", "bob", 40),
+            ("ATOM-OLD-3", "KENL", "alice", 90),
+        ]);
+
+        // migrate is private: only the contract account may call it
+        near_sdk::testing_env!(near_sdk::test_utils::VMContextBuilder::new()
+            .current_account_id("vortex.near".parse().unwrap())
+            .predecessor_account_id("vortex.near".parse().unwrap())
+            .build());
+        let mut contract = SpiralSafeVortex::migrate();
+        assert_eq!(
+            contract.get_version(),
+            (CONTRACT_VERSION.to_string(), CONTRACT_VERSION.to_string())
+        );
+        assert_eq!(contract.owner.as_str(), "owner.near");
+        assert_eq!(contract.get_vortex_state().total_atoms, 0);
+
+        set_caller("owner.near");
+        let tags = vec!["ATOM-OLD-1".to_string(), "ATOM-OLD-2".to_string()];
+        assert_eq!(contract.migrate_legacy_atoms(tags.clone()), 2);
+        assert_eq!(contract.migrate_legacy_atoms(tags), 0);
+
+        // A baseline trail keeps listing its tags until every one is replayed
+        let legacy_trails: LookupMap<String, Vector<String>> = LookupMap::new(b"c");
+        let alice_trail = legacy_trails.get(&"alice".to_string()).unwrap();
+        assert!(alice_trail.iter().any(|tag| tag == "ATOM-OLD-3"));
+        assert!(legacy_trails.get(&"bob".to_string()).is_none());
+
+        assert_eq!(
+            contract.migrate_legacy_atoms(vec!["ATOM-OLD-3".to_string(), "ATOM-NONE".to_string()]),
+            1
+        );
+
+        let vortex = contract.get_vortex_state();
+        assert_eq!(vortex.total_atoms, 3);
+        assert_eq!(vortex.average_coherence, 70);
+        // The baseline threshold of 60 carries over
+        assert_eq!(vortex.snap_in_count, 2);
+        assert_eq!(vortex.contributor_count, 2);
+
+        let repo = contract.get_repo_state("qdi".to_string()).unwrap();
+        assert_eq!(repo.atom_count, 2);
+        assert_eq!(repo.average_coherence, 60);
+        assert_eq!(repo.contributor_count, 2);
+        assert_eq!(contract.get_contributor_atoms("alice".to_string()).len(), 2);
+        let atom = contract.get_atom("ATOM-OLD-3".to_string()).unwrap();
+        assert_eq!(atom.repo, "kenl");
+        assert_eq!(atom.sequence, 2);
+        assert_eq!(atom.recorded_by, None);
+
+        // Baseline entries are gone once replayed
+        let legacy_atoms: LookupMap<String, ATOMOnChainV0> = LookupMap::new(b"a");
+        assert!(legacy_atoms.get(&"ATOM-OLD-1".to_string()).is_none());
+        assert!(contract.legacy_repos.is_none());
+        assert!(legacy_trails.get(&"alice".to_string()).is_none());
+    }
+
+    #[test]
+    fn test_safe_average() {
+        assert_eq!(safe_average(0, 0), 0);
+        assert_eq!(safe_average(250, 0), 0);
+        assert_eq!(safe_average(240, 3), 80);
+        assert_eq!(safe_average(200, 3), 66);
+        assert_eq!(safe_average(100, 1), 100);
+    }
+
+    #[test]
+    fn test_recent_atoms_feed() {
         let context = get_context();
         near_sdk::testing_env!(context);
 
         let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.record_atom(make_atom("ATOM-FEED-1", "QDI", "alice", 40, &[]));
+        contract.record_atom(make_atom("ATOM-FEED-2", "KENL", "bob", 50, &[]));
+        contract.record_atom(make_atom("ATOM-FEED-3", "AWI", "carol", 60, &[]));
+        contract.record_atom(make_atom("ATOM-FEED-4", "QDI", "bob", 70, &[]));
+        contract.amend_atom(
+            "ATOM-FEED-3".to_string(),
+            make_atom("ATOM-FEED-3", "AWI", "carol", 65, &[]),
+        );
 
-        let atom = ATOMOnChain {
-            atom_tag: "ATOM-TEST-001".to_string(),
-            repo: "QDI".to_string(),
-            coherence_score: 75,
-            phases_passed: vec!["KENL".to_string(), "AWI".to_string()],
-            markers: vec!["WAVE".to_string(), "PASS".to_string()],
-            contributor: "toolate28".to_string(),
-            timestamp: "2026-01-17T00:00:00Z".to_string(),
-            commit_hash: "abc123".to_string(),
-            pr_number: Some(42),
+        let tags = |atoms: &[ATOMOnChain]| -> Vec<String> {
+            atoms.iter().map(|atom| atom.atom_tag.clone()).collect()
         };
+        let newest = contract.get_recent_atoms(0, 2);
+        assert_eq!(tags(&newest), vec!["ATOM-FEED-4", "ATOM-FEED-3"]);
+        assert_eq!(newest[1].coherence_score, 65);
 
-        let result = contract.record_atom(atom.clone());
-        assert!(!result.is_empty());
+        let older = contract.get_recent_atoms(2, 5);
+        assert_eq!(tags(&older), vec!["ATOM-FEED-2", "ATOM-FEED-1"]);
+        assert!(contract.get_recent_atoms(10, 5).is_empty());
+    }
 
-        let retrieved = contract.get_atom("ATOM-TEST-001".to_string());
-        assert!(retrieved.is_some());
-        assert_eq!(retrieved.unwrap().coherence_score, 75);
+    #[test]
+    fn test_min_record_coherence_accepts_at_floor() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        assert_eq!(contract.get_min_record_coherence("QDI".to_string()), 0);
+        contract.set_min_record_coherence(30);
+        contract.set_repo_min_record_coherence("QDI".to_string(), Some(50));
+        assert_eq!(contract.get_min_record_coherence("QDI".to_string()), 50);
+        assert_eq!(contract.get_min_record_coherence("KENL".to_string()), 30);
+
+        contract.record_atom(make_atom("ATOM-FLOOR-1", "QDI", "alice", 50, &[]));
+        contract.record_atom(make_atom("ATOM-FLOOR-2", "KENL", "alice", 40, &[]));
+        assert_eq!(contract.get_vortex_state().total_atoms, 2);
     }
 
     #[test]
-    fn test_snap_in_detection() {
+    #[should_panic(expected = "Coherence below the recording minimum of 50 for qdi")]
+    fn test_min_record_coherence_rejects_below_floor() {
         let context = get_context();
         near_sdk::testing_env!(context);
 
         let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.set_repo_min_record_coherence("QDI".to_string(), Some(50));
+        contract.record_atom(make_atom("ATOM-FLOOR-1", "QDI", "alice", 49, &[]));
+    }
 
-        // Record atoms until snap-in
-        for i in 0..5 {
-            let atom = ATOMOnChain {
-                atom_tag: format!("ATOM-TEST-{}", i),
-                repo: "QDI".to_string(),
-                coherence_score: 80,  // Above threshold
-                phases_passed: vec![],
-                markers: vec!["WAVE".to_string()],
-                contributor: "test".to_string(),
-                timestamp: "2026-01-17".to_string(),
-                commit_hash: "abc".to_string(),
-                pr_number: None,
-            };
-            contract.record_atom(atom);
+    #[test]
+    #[should_panic(expected = "Coherence below the recording minimum of 50 for qdi")]
+    fn test_min_record_coherence_rejects_amend_below_floor() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.set_repo_min_record_coherence("QDI".to_string(), Some(50));
+        contract.record_atom(make_atom("ATOM-FLOOR-1", "QDI", "alice", 50, &[]));
+        contract.amend_atom(
+            "ATOM-FLOOR-1".to_string(),
+            make_atom("ATOM-FLOOR-1", "QDI", "alice", 49, &[]),
+        );
+    }
+
+    #[test]
+    fn test_repo_contributor_count() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        for i in 0..3 {
+            let tag = format!("ATOM-BUS-A{}", i);
+            contract.record_atom(make_atom(&tag, "QDI", "alice", 80, &[]));
         }
+        contract.record_atom(make_atom("ATOM-BUS-B0", "QDI", "bob", 70, &[]));
+        contract.record_atom(make_atom("ATOM-BUS-B1", "QDI", "bob", 70, &[]));
 
-        let (snap_in, coherence) = contract.check_ecosystem_snap_in();
-        assert!(snap_in);
-        assert_eq!(coherence, 80);
+        let state = contract.get_repo_state("QDI".to_string()).unwrap();
+        assert_eq!(state.atom_count, 5);
+        assert_eq!(state.contributor_count, 2);
+    }
+
+    #[test]
+    fn test_snap_in_threshold_cas() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.set_snap_in_threshold(75);
+        contract.set_snap_in_threshold_cas(75, 80);
+        assert_eq!(contract.get_effective_threshold("QDI".to_string()), 80);
+    }
+
+    #[test]
+    #[should_panic(expected = "Threshold changed concurrently")]
+    fn test_snap_in_threshold_cas_rejects_stale() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.set_snap_in_threshold(75);
+        contract.set_snap_in_threshold_cas(70, 80);
+    }
+
+    #[test]
+    fn test_commit_hash_accepts_short_and_full() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        let mut short = make_atom("ATOM-HASH-1", "QDI", "alice", 80, &[]);
+        short.commit_hash = "1a2b3c4".to_string();
+        contract.record_atom(short);
+
+        let mut full = make_atom("ATOM-HASH-2", "QDI", "alice", 80, &[]);
+        full.commit_hash = "0123456789abcdef0123456789abcdef01234567".to_string();
+        contract.record_atom(full);
+        assert_eq!(contract.get_vortex_state().total_atoms, 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "Commit hash must be 7-64 lowercase hex characters")]
+    fn test_commit_hash_rejects_non_hex() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        let mut atom = make_atom("ATOM-HASH-1", "QDI", "alice", 80, &[]);
+        atom.commit_hash = "https://github.com/toolate28/QDI/commit/1a2b3c4".to_string();
+        contract.record_atom(atom);
+    }
+
+    #[test]
+    fn test_commit_hash_validation_disabled() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.set_commit_hash_validation(false);
+        let mut atom = make_atom("ATOM-HASH-1", "QDI", "alice", 80, &[]);
+        atom.commit_hash = "svn-r1024".to_string();
+        contract.record_atom(atom);
+        assert_eq!(contract.get_atom("ATOM-HASH-1".to_string()).unwrap().commit_hash, "svn-r1024");
+    }
+
+    #[test]
+    fn test_audit_repo_pages() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        for (i, coherence) in [40, 60, 80, 90, 30].iter().enumerate() {
+            let tag = format!("ATOM-AUDIT-{}", i);
+            contract.record_atom(make_atom(&tag, "QDI", "alice", *coherence, &[]));
+        }
+        contract.flag_atom("ATOM-AUDIT-4".to_string(), "Bad run".to_string());
+        contract.invalidate_atom("ATOM-AUDIT-4".to_string());
+
+        assert_eq!(contract.audit_repo("QDI".to_string(), 0, 2), (100, 2));
+        assert_eq!(contract.audit_repo("QDI".to_string(), 2, 2), (170, 2));
+        assert_eq!(contract.audit_repo("QDI".to_string(), 4, 2), (0, 0));
+
+        let state = contract.get_repo_state("QDI".to_string()).unwrap();
+        assert_eq!(state.total_coherence, 270);
+        assert_eq!(state.atom_count, 4);
+    }
+
+    #[test]
+    fn test_recompute_repo_restores_totals() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        for (i, coherence) in [40, 60, 80].iter().enumerate() {
+            let tag = format!("ATOM-FIX-{}", i);
+            contract.record_atom(make_atom(&tag, "QDI", "alice", *coherence, &[]));
+        }
+        contract.record_atom(make_atom("ATOM-FIX-KENL", "KENL", "alice", 100, &[]));
+
+        contract.corrupt_repo_totals(&"qdi".to_string(), 500, 5);
+        assert_eq!(contract.get_repo_state("QDI".to_string()).unwrap().average_coherence, 100);
+
+        assert_eq!(contract.recompute_repo("QDI".to_string(), 0, 2, false), 2);
+        assert_eq!(contract.recompute_repo("QDI".to_string(), 2, 2, true), 3);
+
+        let state = contract.get_repo_state("QDI".to_string()).unwrap();
+        assert_eq!(state.total_coherence, 180);
+        assert_eq!(state.atom_count, 3);
+        assert_eq!(state.average_coherence, 60);
+
+        let vortex = contract.get_vortex_state();
+        assert_eq!(vortex.total_coherence_sum, 280);
+        assert_eq!(vortex.total_atoms, 4);
+        assert_eq!(vortex.average_coherence, 70);
+    }
+
+    #[test]
+    #[should_panic(expected = "Recompute pages must be contiguous")]
+    fn test_recompute_repo_rejects_gap() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        for i in 0..4 {
+            let tag = format!("ATOM-FIX-{}", i);
+            contract.record_atom(make_atom(&tag, "QDI", "alice", 80, &[]));
+        }
+        contract.recompute_repo("QDI".to_string(), 0, 1, false);
+        contract.recompute_repo("QDI".to_string(), 2, 2, true);
+    }
+
+    #[test]
+    fn test_events_carry_configured_metadata() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        assert_eq!(
+            contract.get_event_metadata(),
+            (DEFAULT_EVENT_STANDARD.to_string(), DEFAULT_EVENT_VERSION.to_string())
+        );
+        contract.set_event_metadata("spiralsafe-fork".to_string(), "2.1.0".to_string());
+        contract.record_atom(make_atom("ATOM-EVENT-1", "QDI", "alice", 80, &[]));
+
+        let logs = near_sdk::test_utils::get_logs();
+        let event = logs
+            .iter()
+            .find_map(|log| log.strip_prefix("EVENT_JSON:"))
+            .expect("No event emitted");
+        let event: near_sdk::serde_json::Value = near_sdk::serde_json::from_str(event).unwrap();
+        assert_eq!(event["standard"], "spiralsafe-fork");
+        assert_eq!(event["version"], "2.1.0");
+        assert_eq!(event["event"], "atom_recorded");
+        assert_eq!(event["data"][0]["atom_tag"], "ATOM-EVENT-1");
+        assert_eq!(event["data"][0]["snap_in"], true);
+    }
+
+    #[test]
+    fn test_attribution_marker_counts() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.record_atom(make_atom("ATOM-MC-1", "QDI", "alice", 80, &["WAVE", "PASS"]));
+        contract.record_atom(make_atom("ATOM-MC-2", "KENL", "alice", 70, &["WAVE"]));
+        contract.record_atom(make_atom("ATOM-MC-3", "QDI", "alice", 90, &["PASS", "WAVE"]));
+        contract.record_atom(make_atom("ATOM-MC-4", "QDI", "alice", 40, &["FAIL"]));
+        contract.record_atom(make_atom("ATOM-MC-5", "QDI", "bob", 40, &["FAIL"]));
+
+        assert_eq!(
+            contract.get_attribution_marker_counts("alice".to_string()),
+            vec![
+                ("WAVE".to_string(), 3),
+                ("PASS".to_string(), 2),
+                ("FAIL".to_string(), 1),
+            ]
+        );
+        assert!(contract.get_attribution_marker_counts("nobody".to_string()).is_empty());
+    }
+
+    fn partner_state(average_coherence: u8, total_atoms: u64) -> VortexState {
+        VortexState {
+            total_atoms,
+            total_coherence_sum: average_coherence as u64 * total_atoms,
+            average_coherence,
+            snap_in_count: 0,
+            contributor_count: 1,
+            last_update: "0".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_sync_partner_coherence_calls_partner() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.set_partner_contract("partner.near".parse().unwrap());
+        let _ = contract.sync_partner_coherence();
+
+        let receipts = near_sdk::test_utils::get_created_receipts();
+        let call = receipts
+            .iter()
+            .find(|r| r.receiver_id.as_str() == "partner.near")
+            .expect("No receipt to the partner contract");
+        match &call.actions[0] {
+            near_sdk::mock::MockAction::FunctionCallWeight { method_name, .. } => {
+                assert_eq!(method_name.as_slice(), b"get_vortex_state");
+            }
+            _ => panic!("Expected a get_vortex_state function call"),
+        }
+    }
+
+    #[test]
+    fn test_partner_state_callback() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.record_atom(make_atom("ATOM-PARTNER-1", "QDI", "alice", 80, &[]));
+        assert_eq!(contract.get_combined_coherence(), 80);
+
+        // Callbacks are private: only the contract account may call them
+        let contract_account: AccountId = "vortex.near".parse().unwrap();
+        near_sdk::testing_env!(near_sdk::test_utils::VMContextBuilder::new()
+            .current_account_id(contract_account.clone())
+            .predecessor_account_id(contract_account)
+            .build());
+
+        // (80 * 1 + 40 * 3) / 4
+        contract.on_partner_state(Ok(partner_state(40, 3)));
+        assert_eq!(contract.get_combined_coherence(), 50);
+
+        contract.on_partner_state(Err(PromiseError::Failed));
+        assert_eq!(contract.get_combined_coherence(), 50);
+    }
+
+    #[test]
+    fn test_register_contributors_feeds_leaderboard() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.record_atom(make_atom("ATOM-LB-1", "QDI", "alice", 60, &[]));
+        contract.record_atom(make_atom("ATOM-LB-2", "KENL", "alice", 80, &[]));
+        contract.record_atom(make_atom("ATOM-LB-3", "QDI", "bob", 90, &[]));
+        contract.forget_contributor_stats(&"alice".to_string());
+        contract.forget_contributor_stats(&"bob".to_string());
+        assert!(contract.get_contributor_leaderboard(10, 10).is_empty());
+
+        assert!(contract.register_contributor("alice".to_string(), 10));
+        assert!(contract.register_contributor("bob".to_string(), 10));
+        assert!(!contract.register_contributor("bob".to_string(), 10));
+        assert!(!contract.register_contributor("nobody".to_string(), 10));
+
+        assert_eq!(
+            contract.get_contributor_leaderboard(10, 10),
+            vec![("bob".to_string(), 90, 1), ("alice".to_string(), 70, 2)]
+        );
+        assert_eq!(contract.get_contributor_leaderboard(1, 10).len(), 1);
+    }
+
+    #[test]
+    fn test_atom_summaries_match_records() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.record_atom(make_atom("ATOM-SUM-1", "QDI", "alice", 70, &["WAVE"]));
+        contract.record_atom(make_atom("ATOM-SUM-2", "KENL", "bob", 90, &["PASS"]));
+        contract.record_atom(make_atom("ATOM-SUM-3", "QDI", "bob", 50, &[]));
+
+        let full = contract.get_repo_atoms("QDI".to_string(), 10, None);
+        let summaries = contract.get_repo_atom_summaries("QDI".to_string(), 10, None);
+        assert_eq!(summaries.len(), full.len());
+        for (summary, atom) in summaries.iter().zip(full.iter()) {
+            assert_eq!(summary.atom_tag, atom.atom_tag);
+            assert_eq!(summary.repo, atom.repo);
+            assert_eq!(summary.coherence_score, atom.coherence_score);
+            assert_eq!(summary.timestamp, atom.timestamp);
+        }
+
+        let recent = contract.get_recent_atom_summaries(0, 2);
+        let tags: Vec<&str> = recent.iter().map(|s| s.atom_tag.as_str()).collect();
+        assert_eq!(tags, vec!["ATOM-SUM-3", "ATOM-SUM-2"]);
+        assert_eq!(recent[1].coherence_score, 90);
+    }
+
+    #[test]
+    #[should_panic(expected = "ATOM already recorded")]
+    fn test_record_rejects_existing_tag() {
+        set_caller("uploader.near");
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        contract.record_atom(make_atom("ATOM-TAKEN-1", "QDI", "alice", 80, &[]));
+
+        set_caller("other-uploader.near");
+        contract.record_atom(make_atom("ATOM-TAKEN-1", "QDI", "mallory", 10, &[]));
+    }
+
+    #[test]
+    fn test_snap_in_count_ignores_overwrites() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        let atom = make_atom("ATOM-DUP-1", "QDI", "alice", 90, &[]);
+        contract.record_atom_idempotent(atom.clone(), Some("nonce-1".to_string()));
+        // Retrying under the same idempotency key is a no-op
+        contract.record_atom_idempotent(atom, Some("nonce-1".to_string()));
+        // A plain re-record of the tag is rejected before touching any tally
+        let overwrite = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            contract.record_atom(make_atom("ATOM-DUP-1", "QDI", "alice", 95, &[]));
+        }));
+        assert!(overwrite.is_err());
+
+        let state = contract.get_vortex_state();
+        assert_eq!(state.total_atoms, 1);
+        assert_eq!(state.snap_in_count, 1);
+        assert_eq!(contract.get_repo_state("QDI".to_string()).unwrap().atom_count, 1);
+        assert_eq!(contract.get_contributor_stats("alice".to_string()).unwrap().atom_count, 1);
+        assert_eq!(contract.get_atom("ATOM-DUP-1".to_string()).unwrap().coherence_score, 90);
+    }
+
+    #[test]
+    fn test_repo_aggregation_modes() {
+        let context = get_context();
+        near_sdk::testing_env!(context);
+
+        let mut contract = SpiralSafeVortex::new("owner.near".parse().unwrap());
+        for (i, coherence) in [40, 90, 60, 80, 20].iter().enumerate() {
+            let tag = format!("ATOM-AGG-{}", i);
+            contract.record_atom(make_atom(&tag, "QDI", "alice", *coherence, &[]));
+        }
+
+        // 290 / 5
+        assert_eq!(contract.get_repo_coherence_scored("QDI".to_string()), 58);
+
+        // Newest three: 60, 80, 20
+        contract.set_repo_aggregation_mode("QDI".to_string(), AggregationMode::MedianRecent(3));
+        assert_eq!(contract.get_repo_coherence_scored("QDI".to_string()), 60);
+
+        // Newest four: 90, 60, 80, 20 -> (60 + 80) / 2
+        contract.set_repo_aggregation_mode("QDI".to_string(), AggregationMode::MedianRecent(4));
+        assert_eq!(contract.get_repo_coherence_scored("QDI".to_string()), 70);
+
+        contract.set_repo_aggregation_mode("QDI".to_string(), AggregationMode::Min);
+        assert_eq!(contract.get_repo_coherence_scored("QDI".to_string()), 20);
+
+        contract.set_repo_aggregation_mode("QDI".to_string(), AggregationMode::Mean);
+        assert_eq!(contract.get_repo_coherence_scored("QDI".to_string()), 58);
+        assert_eq!(contract.get_repo_state("QDI".to_string()).unwrap().average_coherence, 58);
+        assert_eq!(contract.get_repo_coherence_scored("unknown".to_string()), 0);
     }
 }